@@ -0,0 +1,698 @@
+mod schedule;
+mod show;
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+use token_biu::constants::{Period, BULK_UNLOCK, DEFAULT_PERIOD, MONTHLY_UNLOCK, YEAR1_START};
+use token_biu_client::{
+    accounts, calendar, instructions, pda, CarryOver, SaleConfig, TailPolicy, UnsoldPolicy, HOOK_ID,
+};
+
+/// Operate the token_biu sale.
+#[derive(Parser)]
+#[command(name = "token-biu-admin", version)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, short = 'u', default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Keypair of the sale authority, which signs and pays
+    #[arg(long, short = 'k', default_value = "new-keypair.json")]
+    keypair: PathBuf,
+
+    /// SaleConfig account; defaults to the only one owned by the program
+    #[arg(long)]
+    sale_config: Option<Pubkey>,
+
+    /// Simulate the transaction and print its logs instead of sending it
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a new SaleConfig
+    Initialize {
+        /// Keypair file for the new SaleConfig account
+        #[arg(long)]
+        sale_config_keypair: PathBuf,
+        /// Wallet receiving the SOL paid by buyers
+        #[arg(long)]
+        recipient: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        token_price_usd: f64,
+        #[arg(long)]
+        mint_decimals: u64,
+        /// Tokens (in base units) a wallet may buy per day
+        #[arg(long)]
+        purchase_limit: u64,
+    },
+    /// Set the per-wallet daily purchase limit (in base units)
+    SetLimit { new_limit: u64 },
+    /// Open purchases at a start time and close them at an end time
+    SetSaleWindow {
+        /// Unix timestamp purchases open at
+        #[arg(long)]
+        start_time: i64,
+        /// Unix timestamp purchases close at; 0 for no end
+        #[arg(long, default_value_t = 0)]
+        end_time: i64,
+    },
+    /// Move the sale from draft to active
+    Activate,
+    /// Close the sale to purchases before its end time
+    EndSale,
+    /// Record that the sale's end time has passed; anyone may send it
+    SyncState,
+    /// Choose what `finalize` does with the tokens left in the vault
+    SetUnsoldPolicy {
+        #[arg(value_enum)]
+        policy: UnsoldPolicyArg,
+    },
+    /// Wind an ended sale down, handling the unsold vault tokens per the unsold policy
+    Finalize,
+    /// Close a finalized sale's accounts and reclaim their rent
+    CloseSale,
+    /// Close the keypair's own WalletPurchase record and reclaim its rent
+    ClosePurchaseRecord,
+    /// Rewrite a WalletPurchase record from an earlier program version in the current layout
+    MigratePurchaseRecord {
+        /// Wallet whose record to migrate; defaults to the keypair's own
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Lock each purchase in the buyer's wallet for this many seconds; 0 turns lockups off
+    SetLockup { lockup_duration: i64 },
+    /// Create the transfer hook's extra-account list for the sale's Token-2022 mint
+    InitTransferHook,
+    /// Stop accepting purchases
+    Pause,
+    /// Accept purchases again
+    Resume,
+    /// Change the wallet receiving the SOL paid by buyers
+    ChangeRecipient { new_recipient: Pubkey },
+    /// Hand the sale over to a new authority
+    ChangeAuthority { new_authority: Pubkey },
+    /// Move tokens (in base units) from the authority's token account into the vault
+    Fund { token_amount: u64 },
+    /// Create the schedule account with room for this many periods
+    InitMonthlyLimits {
+        periods: u16,
+        /// Period length used by `generate-schedule`
+        #[arg(long, value_enum, default_value_t = PeriodArg::from(DEFAULT_PERIOD))]
+        period: PeriodArg,
+        /// Length in seconds of a `custom` period
+        #[arg(long, required_if_eq("period", "custom"))]
+        period_seconds: Option<i64>,
+    },
+    /// Replace the vesting schedule with a JSON file of `{ "timestamp", "limit" }` periods
+    SetMonthlyLimits { file: PathBuf },
+    /// Add the periods in a schedule file after the current last period
+    AppendMonthlyLimits { file: PathBuf },
+    /// Replace every period from an index on with the periods in a schedule file
+    AmendSchedule {
+        /// First period to replace; it must not have been reached yet
+        #[arg(long)]
+        from_index: u16,
+        file: PathBuf,
+    },
+    /// Generate the schedule on-chain from a start time and unlock amounts,
+    /// with the period length chosen at `init-monthly-limits`
+    GenerateSchedule {
+        /// Unix timestamp of the first period
+        #[arg(long, default_value_t = YEAR1_START)]
+        start_time: i64,
+        /// Whole tokens unlocked for the public sale each period
+        #[arg(long, default_value_t = MONTHLY_UNLOCK)]
+        period_unlock: u64,
+        /// Whole tokens unlocked for treasury withdrawals each period
+        #[arg(long, default_value_t = 0)]
+        treasury_unlock: u64,
+        /// Whole tokens unlocked in the two bulk unlocks combined
+        #[arg(long, default_value_t = BULK_UNLOCK)]
+        bulk_unlock: u64,
+    },
+    /// Unlock every period reached so far; anyone may send it
+    Advance,
+    /// Choose what happens to unsold sale allocations and after the final period
+    SetSchedulePolicy {
+        #[arg(long, value_enum)]
+        carry_over: CarryOverArg,
+        #[arg(long, value_enum)]
+        tail: TailPolicyArg,
+    },
+    /// Print a schedule file unlocking on the 1st of each month, 00:00 UTC
+    CalendarSchedule {
+        /// First month, as YYYY-MM
+        #[arg(long)]
+        start: String,
+        #[arg(long)]
+        months: usize,
+        /// Tokens (in base units) unlocked for the sale each month
+        #[arg(long)]
+        limit: u64,
+        /// Tokens (in base units) unlocked for the treasury each month
+        #[arg(long, default_value_t = 0)]
+        treasury_limit: u64,
+    },
+    /// Mint each purchase instead of transferring from the vault; the sale
+    /// authority PDA must hold the mint authority
+    EnableMinting {
+        /// Cap on the mint's total supply, in base units
+        #[arg(long)]
+        max_supply: u64,
+    },
+    /// Sell from the pre-funded vault again
+    DisableMinting,
+    /// Hand the mint authority back from the sale authority PDA, turning minting off
+    ReturnMintAuthority {
+        /// New mint authority; defaults to the keypair
+        #[arg(long)]
+        new_authority: Option<Pubkey>,
+    },
+    /// Enforce the vesting schedule on purchases and withdrawals
+    EnableVesting,
+    /// Stop enforcing the vesting schedule on purchases
+    DisableVesting,
+    /// Withdraw vault tokens (in base units) from the treasury allowance to the authority's token account
+    Withdraw { token_amount: u64 },
+    /// Pretty-print every program account
+    Show,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum PeriodArg {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    /// `--period-seconds` long
+    Custom,
+    /// 00:00 UTC on the 1st of each month
+    CalendarMonthly,
+}
+
+impl From<Period> for PeriodArg {
+    fn from(period: Period) -> Self {
+        match period {
+            Period::Hourly => PeriodArg::Hourly,
+            Period::Daily => PeriodArg::Daily,
+            Period::Weekly => PeriodArg::Weekly,
+            Period::Monthly => PeriodArg::Monthly,
+            Period::Custom(_) => PeriodArg::Custom,
+            Period::CalendarMonthly => PeriodArg::CalendarMonthly,
+        }
+    }
+}
+
+impl PeriodArg {
+    fn period(self, seconds: Option<i64>) -> Result<Period> {
+        Ok(match self {
+            PeriodArg::Hourly => Period::Hourly,
+            PeriodArg::Daily => Period::Daily,
+            PeriodArg::Weekly => Period::Weekly,
+            PeriodArg::Monthly => Period::Monthly,
+            PeriodArg::Custom => match seconds {
+                Some(seconds) if seconds > 0 => Period::Custom(seconds),
+                _ => bail!("a custom period needs a positive --period-seconds"),
+            },
+            PeriodArg::CalendarMonthly => Period::CalendarMonthly,
+        })
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum CarryOverArg {
+    /// Unsold allocation stays available to buyers
+    Rollover,
+    /// Unsold allocation moves to the treasury allowance
+    ExpireToTreasury,
+    /// Unsold allocation is burned from the vault
+    Burn,
+}
+
+impl From<CarryOverArg> for CarryOver {
+    fn from(carry_over: CarryOverArg) -> Self {
+        match carry_over {
+            CarryOverArg::Rollover => CarryOver::Rollover,
+            CarryOverArg::ExpireToTreasury => CarryOver::ExpireToTreasury,
+            CarryOverArg::Burn => CarryOver::Burn,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum TailPolicyArg {
+    /// The whole vault is available to both allowances
+    UnlockVault,
+    /// The vault beyond the sale allowance goes to the treasury
+    TreasuryOnly,
+    /// Nothing beyond the scheduled allocations unlocks
+    Locked,
+}
+
+impl From<TailPolicyArg> for TailPolicy {
+    fn from(tail: TailPolicyArg) -> Self {
+        match tail {
+            TailPolicyArg::UnlockVault => TailPolicy::UnlockVault,
+            TailPolicyArg::TreasuryOnly => TailPolicy::TreasuryOnly,
+            TailPolicyArg::Locked => TailPolicy::Locked,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum UnsoldPolicyArg {
+    /// Send the unsold tokens to the authority's token account
+    ReturnToTreasury,
+    /// Burn the unsold tokens
+    Burn,
+}
+
+impl From<UnsoldPolicyArg> for UnsoldPolicy {
+    fn from(policy: UnsoldPolicyArg) -> Self {
+        match policy {
+            UnsoldPolicyArg::ReturnToTreasury => UnsoldPolicy::ReturnToTreasury,
+            UnsoldPolicyArg::Burn => UnsoldPolicy::Burn,
+        }
+    }
+}
+
+/// Month start of a `YYYY-MM` string.
+fn parse_month(month: &str) -> Result<i64> {
+    let (year, month_of_year) = month
+        .split_once('-')
+        .and_then(|(year, month)| Some((year.parse().ok()?, month.parse().ok()?)))
+        .ok_or_else(|| anyhow!("expected YYYY-MM, got {month}"))?;
+
+    calendar::month_start_timestamp(year, month_of_year)
+        .ok_or_else(|| anyhow!("{month} is not a valid month"))
+}
+
+/// Print a schedule file with one period per calendar month.
+fn print_calendar_schedule(start: &str, months: usize, limit: u64, treasury_limit: u64) -> Result<()> {
+    let timestamps = calendar::month_starts(parse_month(start)?, months)
+        .ok_or_else(|| anyhow!("{months} months from {start} run past year 9999"))?;
+    let entries: Vec<_> = timestamps
+        .into_iter()
+        .map(|timestamp| schedule::PeriodEntry {
+            timestamp,
+            limit,
+            treasury_limit,
+        })
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&entries)?);
+
+    Ok(())
+}
+
+/// Whole tokens to base units.
+fn base_units(tokens: u64, decimals: u64) -> Result<u64> {
+    u32::try_from(decimals)
+        .ok()
+        .and_then(|decimals| 10u64.checked_pow(decimals))
+        .and_then(|scale| tokens.checked_mul(scale))
+        .ok_or_else(|| anyhow!("{tokens} tokens overflow at {decimals} decimals"))
+}
+
+struct Admin {
+    client: RpcClient,
+    payer: Keypair,
+    dry_run: bool,
+}
+
+impl Admin {
+    /// Token program owning `mint`: SPL Token or Token-2022.
+    fn token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+        let account = self
+            .client
+            .get_account(mint)
+            .with_context(|| format!("fetching mint {mint}"))?;
+
+        Ok(account.owner)
+    }
+
+    /// Transfer-hook program of `mint`, if it has one.
+    fn transfer_hook(&self, mint: &Pubkey) -> Result<Option<Pubkey>> {
+        let account = self
+            .client
+            .get_account(mint)
+            .with_context(|| format!("fetching mint {mint}"))?;
+
+        Ok(accounts::transfer_hook_program(&account.data)?)
+    }
+
+    /// Append the `token_biu_hook` accounts to an instruction transferring
+    /// out of `source_owner`'s token account, when the sale's mint uses it.
+    fn add_transfer_hook_accounts(
+        &self,
+        instruction: &mut Instruction,
+        config: &SaleConfig,
+        source_owner: &Pubkey,
+    ) -> Result<()> {
+        match self.transfer_hook(&config.token_mint)? {
+            None => {}
+            Some(hook) if hook == HOOK_ID => instruction.accounts.extend(
+                instructions::transfer_hook_accounts(&config.token_mint, source_owner),
+            ),
+            Some(hook) => bail!("mint {} uses unknown transfer hook {hook}", config.token_mint),
+        }
+
+        Ok(())
+    }
+
+    /// Whether `sale_config` has a schedule account.
+    fn has_schedule(&self, sale_config: &Pubkey) -> Result<bool> {
+        Ok(self
+            .client
+            .get_account_with_commitment(&pda::monthly_limits(sale_config).0, self.client.commitment())?
+            .value
+            .is_some())
+    }
+
+    fn sale_config(&self, key: Option<Pubkey>) -> Result<(Pubkey, SaleConfig)> {
+        if let Some(key) = key {
+            let account = self.client.get_account(&key)?;
+            return Ok((key, accounts::sale_config(&account.data)?));
+        }
+
+        let mut configs = show::program_accounts::<SaleConfig>(&self.client)?;
+        match configs.len() {
+            1 => Ok(configs.remove(0)),
+            0 => bail!("no SaleConfig account found for {}", token_biu::ID),
+            found => bail!("{found} SaleConfig accounts found, pass --sale-config"),
+        }
+    }
+
+    fn submit(&self, instruction: Instruction, extra_signers: &[&Keypair]) -> Result<()> {
+        let mut signers: Vec<&Keypair> = vec![&self.payer];
+        signers.extend_from_slice(extra_signers);
+
+        let blockhash = self.client.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+
+        if self.dry_run {
+            let simulation = self.client.simulate_transaction(&tx)?.value;
+            for log in simulation.logs.unwrap_or_default() {
+                println!("{log}");
+            }
+            if let Some(units) = simulation.units_consumed {
+                println!("compute units consumed: {units}");
+            }
+            return match simulation.err {
+                Some(err) => Err(anyhow!("simulation failed: {err}")),
+                None => {
+                    println!("simulation succeeded, nothing was sent");
+                    Ok(())
+                }
+            };
+        }
+
+        let signature = self.client.send_and_confirm_transaction(&tx)?;
+        println!("{signature}");
+
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    if let Command::CalendarSchedule {
+        start,
+        months,
+        limit,
+        treasury_limit,
+    } = &cli.command
+    {
+        return print_calendar_schedule(start, *months, *limit, *treasury_limit);
+    }
+
+    let payer = read_keypair_file(&cli.keypair)
+        .map_err(|err| anyhow!("reading keypair {}: {err}", cli.keypair.display()))?;
+    let admin = Admin {
+        client: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+        dry_run: cli.dry_run,
+    };
+    let authority = admin.payer.pubkey();
+
+    let instruction = match cli.command {
+        Command::Show => return show::show(&admin.client),
+        Command::CalendarSchedule { .. } => unreachable!("printed before loading the keypair"),
+        Command::Initialize {
+            sale_config_keypair,
+            recipient,
+            mint,
+            token_price_usd,
+            mint_decimals,
+            purchase_limit,
+        } => {
+            let sale_config = read_keypair_file(&sale_config_keypair).map_err(|err| {
+                anyhow!("reading keypair {}: {err}", sale_config_keypair.display())
+            })?;
+            let instruction = instructions::initialize_sale(
+                authority,
+                sale_config.pubkey(),
+                recipient,
+                mint,
+                token_price_usd,
+                mint_decimals,
+                purchase_limit,
+            );
+            return admin.submit(instruction, &[&sale_config]);
+        }
+        Command::SetLimit { new_limit } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::set_purchase_limit(sale_config, authority, new_limit)
+        }
+        Command::SetSaleWindow { start_time, end_time } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::set_sale_window(sale_config, authority, start_time, end_time)
+        }
+        Command::Activate => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::activate_sale(sale_config, authority)
+        }
+        Command::EndSale => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::end_sale(sale_config, authority)
+        }
+        Command::SyncState => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::sync_sale_state(sale_config)
+        }
+        Command::SetUnsoldPolicy { policy } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::set_unsold_policy(sale_config, authority, policy.into())
+        }
+        Command::Finalize => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            let mut instruction = instructions::finalize_sale(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                admin.has_schedule(&sale_config)?,
+            );
+            admin.add_transfer_hook_accounts(&mut instruction, &config, &config.sale_authority)?;
+            instruction
+        }
+        Command::CloseSale => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            instructions::close_sale(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                admin.has_schedule(&sale_config)?,
+            )
+        }
+        Command::ClosePurchaseRecord => {
+            let account = admin.client.get_account(&pda::wallet_purchase(&authority).0)?;
+            let purchase = accounts::wallet_purchase(&account.data)?;
+            instructions::close_wallet_purchase(authority, purchase.sale_config)
+        }
+        Command::MigratePurchaseRecord { wallet } => {
+            instructions::migrate_wallet_purchase(authority, wallet.unwrap_or(authority))
+        }
+        Command::SetLockup { lockup_duration } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::set_lockup_duration(sale_config, authority, lockup_duration)
+        }
+        Command::InitTransferHook => {
+            let (_, config) = admin.sale_config(cli.sale_config)?;
+            instructions::initialize_transfer_hook(authority, config.token_mint)
+        }
+        Command::Pause => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::pause_sale(sale_config, authority)
+        }
+        Command::Resume => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::resume_sale(sale_config, authority)
+        }
+        Command::ChangeRecipient { new_recipient } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::change_recipient(sale_config, authority, new_recipient)
+        }
+        Command::ChangeAuthority { new_authority } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::change_config_authority(sale_config, authority, new_authority)
+        }
+        Command::Fund { token_amount } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            let mut instruction = instructions::fund_vault(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                token_amount,
+            );
+            admin.add_transfer_hook_accounts(&mut instruction, &config, &authority)?;
+            instruction
+        }
+        Command::InitMonthlyLimits {
+            periods,
+            period,
+            period_seconds,
+        } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            let period = period.period(period_seconds)?;
+            instructions::initialize_monthly_limits(sale_config, authority, periods, period)
+        }
+        Command::SetMonthlyLimits { file } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            let entries = schedule::read(&file)?;
+            let (limits, treasury_limits, timestamps) = schedule::split(&entries)
+                .with_context(|| format!("invalid schedule {}", file.display()))?;
+            instructions::set_monthly_limits(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                limits,
+                treasury_limits,
+                timestamps,
+            )
+        }
+        Command::AppendMonthlyLimits { file } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            let entries = schedule::read(&file)?;
+            let (limits, treasury_limits, timestamps) = schedule::split(&entries)
+                .with_context(|| format!("invalid schedule {}", file.display()))?;
+            instructions::append_monthly_limits(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                limits,
+                treasury_limits,
+                timestamps,
+            )
+        }
+        Command::AmendSchedule { from_index, file } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            let entries = schedule::read(&file)?;
+            let (limits, treasury_limits, timestamps) = schedule::split_amendment(&entries)
+                .with_context(|| format!("invalid schedule {}", file.display()))?;
+            instructions::amend_schedule(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                from_index,
+                limits,
+                treasury_limits,
+                timestamps,
+            )
+        }
+        Command::GenerateSchedule {
+            start_time,
+            period_unlock,
+            treasury_unlock,
+            bulk_unlock,
+        } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            instructions::generate_monthly_limits(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                start_time,
+                base_units(period_unlock, config.mint_decimals)?,
+                base_units(treasury_unlock, config.mint_decimals)?,
+                base_units(bulk_unlock, config.mint_decimals)?,
+            )
+        }
+        Command::Advance => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::advance_schedule(sale_config)
+        }
+        Command::SetSchedulePolicy { carry_over, tail } => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::set_schedule_policy(sale_config, authority, carry_over.into(), tail.into())
+        }
+        Command::EnableMinting { max_supply } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            instructions::set_sale_mode(sale_config, authority, config.token_mint, true, max_supply)
+        }
+        Command::DisableMinting => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            instructions::set_sale_mode(sale_config, authority, config.token_mint, false, config.max_supply)
+        }
+        Command::ReturnMintAuthority { new_authority } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            instructions::return_mint_authority(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                new_authority.unwrap_or(authority),
+            )
+        }
+        Command::EnableVesting => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::enable_vesting(sale_config, authority)
+        }
+        Command::DisableVesting => {
+            let (sale_config, _) = admin.sale_config(cli.sale_config)?;
+            instructions::disable_vesting(sale_config, authority)
+        }
+        Command::Withdraw { token_amount } => {
+            let (sale_config, config) = admin.sale_config(cli.sale_config)?;
+            let mut instruction = instructions::withdraw_tokens(
+                sale_config,
+                authority,
+                config.token_mint,
+                admin.token_program(&config.token_mint)?,
+                token_amount,
+            );
+            admin.add_transfer_hook_accounts(&mut instruction, &config, &config.sale_authority)?;
+            instruction
+        }
+    };
+
+    admin.submit(instruction, &[])
+}
@@ -0,0 +1,63 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// One vesting period as written in a schedule file.
+#[derive(Deserialize, Serialize)]
+pub struct PeriodEntry {
+    /// Unix timestamp at which the period starts
+    pub timestamp: i64,
+    /// Tokens (in base units) unlocked for the public sale when the period starts
+    pub limit: u64,
+    /// Tokens (in base units) unlocked for treasury withdrawals; defaults to 0
+    #[serde(default)]
+    pub treasury_limit: u64,
+}
+
+/// A schedule as the program takes it: sale limits, treasury limits and
+/// timestamps.
+pub type Schedule = (Vec<u64>, Vec<u64>, Vec<i64>);
+
+/// Read a schedule file: a JSON array of
+/// `{ "timestamp": .., "limit": .., "treasury_limit": .. }`.
+pub fn read(path: &Path) -> Result<Vec<PeriodEntry>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+
+    serde_json::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Split a schedule into the limit and timestamp lists the program takes.
+pub fn split(entries: &[PeriodEntry]) -> Result<Schedule> {
+    if entries.is_empty() {
+        bail!("schedule has no periods");
+    }
+    if let Some(pair) = entries.windows(2).find(|pair| pair[1].timestamp <= pair[0].timestamp) {
+        bail!(
+            "timestamps must be strictly increasing, {} follows {}",
+            pair[1].timestamp,
+            pair[0].timestamp
+        );
+    }
+
+    Ok(unzip(entries))
+}
+
+/// Like `split`, but an empty schedule is allowed: amending with no
+/// periods drops everything from the amend index on.
+pub fn split_amendment(entries: &[PeriodEntry]) -> Result<Schedule> {
+    if entries.is_empty() {
+        return Ok(unzip(entries));
+    }
+
+    split(entries)
+}
+
+fn unzip(entries: &[PeriodEntry]) -> Schedule {
+    (
+        entries.iter().map(|entry| entry.limit).collect(),
+        entries.iter().map(|entry| entry.treasury_limit).collect(),
+        entries.iter().map(|entry| entry.timestamp).collect(),
+    )
+}
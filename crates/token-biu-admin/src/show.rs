@@ -0,0 +1,144 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use anyhow::Result;
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use token_biu_client::{accounts, MonthlyLimits, SaleConfig, SaleStats};
+
+/// Every program-owned account of type `T`, matched by discriminator.
+pub fn program_accounts<T: AccountDeserialize + Discriminator>(
+    client: &RpcClient,
+) -> Result<Vec<(Pubkey, T)>> {
+    decoded_program_accounts(client, |data| T::try_deserialize(&mut &data[..]))
+}
+
+/// Like `program_accounts`, decoding each account's data with `decode`.
+fn decoded_program_accounts<T: Discriminator>(
+    client: &RpcClient,
+    decode: impl Fn(&[u8]) -> anchor_lang::Result<T>,
+) -> Result<Vec<(Pubkey, T)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            T::DISCRIMINATOR.to_vec(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    client
+        .get_program_accounts_with_config(&token_biu::ID, config)?
+        .into_iter()
+        .map(|(key, account)| Ok((key, decode(&account.data)?)))
+        .collect()
+}
+
+/// Pretty-print every `SaleConfig`, `SaleStats`, `MonthlyLimits` and `WalletPurchase`.
+pub fn show(client: &RpcClient) -> Result<()> {
+    for (key, config) in program_accounts::<SaleConfig>(client)? {
+        print_sale_config(&key, &config);
+    }
+
+    for (key, stats) in program_accounts::<SaleStats>(client)? {
+        print_sale_stats(&key, &stats);
+    }
+
+    for (key, limits) in program_accounts::<MonthlyLimits>(client)? {
+        print_monthly_limits(&key, &limits);
+    }
+
+    // Records from earlier program versions are shown as they will read
+    // once migrated
+    let purchases = decoded_program_accounts(client, accounts::wallet_purchase)?;
+    println!("WalletPurchase accounts: {}", purchases.len());
+    for (key, purchase) in purchases {
+        let locks: Vec<String> = purchase
+            .locks
+            .iter()
+            .map(|lock| format!("{} until {}", lock.amount, lock.unlock_timestamp))
+            .collect();
+        println!(
+            "  {key}  wallet {}  purchased {} ({} lifetime)  last purchase {}  locked [{}]",
+            purchase.wallet,
+            purchase.total_purchased,
+            purchase.lifetime_purchased,
+            purchase.last_purchased_timestamp,
+            locks.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn print_sale_config(key: &Pubkey, config: &SaleConfig) {
+    println!("SaleConfig {key}");
+    println!("  authority              {}", config.authority);
+    println!("  sale authority         {}", config.sale_authority);
+    println!("  recipient              {}", config.recipient);
+    println!("  token mint             {}", config.token_mint);
+    println!("  token price (USD)      {}", config.token_price_usd);
+    println!("  mint decimals          {}", config.mint_decimals);
+    println!("  wallet purchase limit  {}", config.wallet_purchase_limit);
+    println!("  state                  {:?}", config.state);
+    println!("  start time             {}", config.start_time);
+    println!("  end time               {}", config.end_time);
+    println!("  paused                 {}", config.paused);
+    println!("  unsold policy          {:?}", config.unsold_policy);
+    println!("  tokens deposited       {}", config.tokens_deposited);
+    println!("  tokens released        {}", config.tokens_released);
+    println!("  lockup duration (s)    {}", config.lockup_duration);
+    println!("  mint on purchase       {}", config.mint_on_purchase);
+    println!("  max supply             {}", config.max_supply);
+    println!("  tokens minted          {}", config.tokens_minted);
+    println!();
+}
+
+fn print_sale_stats(key: &Pubkey, stats: &SaleStats) {
+    println!("SaleStats {key}");
+    println!("  sale config            {}", stats.sale_config);
+    println!("  SOL raised (lamports)  {}", stats.lamports_raised);
+    println!("  USD raised             {:.2}", stats.usd_raised);
+    println!("  tokens sold            {}", stats.tokens_sold);
+    println!("  unique buyers          {}", stats.unique_buyers);
+    println!("  purchases              {}", stats.purchase_count);
+    println!("  largest purchase       {}", stats.largest_purchase);
+    println!();
+}
+
+fn print_monthly_limits(key: &Pubkey, limits: &MonthlyLimits) {
+    println!("MonthlyLimits {key}");
+    println!("  vesting enabled        {}", limits.is_vesting_enabled);
+    println!("  period                 {:?} ({} s)", limits.period, limits.period.length());
+    println!("  periods unlocked       {}", limits.periods_unlocked);
+    println!("  tokens unlocked        {}", limits.tokens_unlocked);
+    println!("  sale available         {}", limits.sale_available);
+    println!("  tokens sold            {}", limits.tokens_sold);
+    println!("  treasury available     {}", limits.treasury_available);
+    println!("  tokens withdrawn       {}", limits.tokens_withdrawn);
+    println!("  carry-over             {:?}", limits.carry_over);
+    println!("  tail policy            {:?}", limits.tail_policy);
+    println!("  tokens expired         {}", limits.tokens_expired);
+    println!("  pending burn           {}", limits.pending_burn);
+    println!("  tokens burned          {}", limits.tokens_burned);
+    println!(
+        "  {:>3}  {:>12}  {:>20}  {:>20}  {:>20}  {:>20}",
+        "#", "timestamp", "sale limit", "treasury limit", "tokens sold", "SOL raised (lamports)"
+    );
+    let periods = limits
+        .timestamps
+        .iter()
+        .zip(limits.limits.iter())
+        .zip(limits.treasury_limits.iter())
+        .zip(limits.period_tokens_sold.iter())
+        .zip(limits.period_sol_raised.iter());
+    for (index, ((((timestamp, limit), treasury_limit), sold), raised)) in periods.enumerate() {
+        println!("  {index:>3}  {timestamp:>12}  {limit:>20}  {treasury_limit:>20}  {sold:>20}  {raised:>20}");
+    }
+    println!();
+}
@@ -0,0 +1,38 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Result};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use token_biu::states::{MonthlyLimits, SaleConfig, SaleStats, WalletPurchase};
+
+/// Deserialize a `SaleConfig` account, checking its discriminator.
+pub fn sale_config(data: &[u8]) -> Result<SaleConfig> {
+    SaleConfig::try_deserialize(&mut &data[..])
+}
+
+/// Deserialize a `WalletPurchase` account, checking its discriminator.
+/// Records in an earlier layout read as they will once migrated.
+pub fn wallet_purchase(data: &[u8]) -> Result<WalletPurchase> {
+    WalletPurchase::read(data)
+}
+
+/// Deserialize a `MonthlyLimits` account, checking its discriminator.
+pub fn monthly_limits(data: &[u8]) -> Result<MonthlyLimits> {
+    MonthlyLimits::try_deserialize(&mut &data[..])
+}
+
+/// Deserialize a `SaleStats` account, checking its discriminator.
+pub fn sale_stats(data: &[u8]) -> Result<SaleStats> {
+    SaleStats::try_deserialize(&mut &data[..])
+}
+
+/// Transfer-hook program of a mint, `None` for mints without one, including
+/// every SPL Token mint.
+pub fn transfer_hook_program(mint_data: &[u8]) -> Result<Option<Pubkey>> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+
+    Ok(mint
+        .get_extension::<TransferHook>()
+        .ok()
+        .and_then(|hook| Option::<Pubkey>::from(hook.program_id)))
+}
@@ -0,0 +1,660 @@
+//! Builders for every `token_biu` instruction. PDAs, ATAs and program ids
+//! are derived; callers only pass the keys the program can't derive. Builders
+//! touching the vault take the mint's `token_program`, SPL Token or
+//! Token-2022, i.e. the owner of the mint account. For mints using the
+//! `token_biu_hook` transfer hook, append `transfer_hook_accounts` to the
+//! `buy_tokens` and `withdraw_tokens` instructions.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+        sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token;
+use token_biu::{
+    accounts,
+    constants::Period,
+    instruction,
+    states::{CarryOver, SaleConfig, TailPolicy, UnsoldPolicy},
+};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: token_biu::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `sale_config` is a fresh keypair's pubkey; it must also sign.
+pub fn initialize_sale(
+    authority: Pubkey,
+    sale_config: Pubkey,
+    recipient: Pubkey,
+    token_mint: Pubkey,
+    token_price_usd: f64,
+    mint_decimals: u64,
+    purchase_limit: u64,
+) -> Instruction {
+    build(
+        accounts::InitializeSale {
+            authority,
+            sale_config,
+            sale_stats: pda::sale_stats(&sale_config).0,
+            recipient,
+            token_mint,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::InitializeSale {
+            token_price_usd,
+            mint_decimals,
+            purchase_limit,
+        },
+    )
+}
+
+/// `config` is the current state of `sale_config`, used for the recipient,
+/// authority and mint it pins. `buyer` pays for the tokens, which go to
+/// `beneficiary`, charged the purchase limits, which signs too; `payer`
+/// pays the rent of the beneficiary's new accounts. Pass `buyer` for both
+/// when a wallet buys for itself.
+#[allow(clippy::too_many_arguments)]
+pub fn buy_tokens(
+    sale_config: Pubkey,
+    config: &SaleConfig,
+    token_program: Pubkey,
+    buyer: Pubkey,
+    payer: Pubkey,
+    beneficiary: Pubkey,
+    price_update: Pubkey,
+    sol_amount: u64,
+) -> Instruction {
+    build(
+        accounts::BuyTokens {
+            buyer,
+            payer,
+            sale_authority: config.recipient,
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            beneficiary,
+            sale_config,
+            authority: config.authority,
+            mint: config.token_mint,
+            program_token_account: pda::program_token_account(&sale_config, &config.token_mint, &token_program),
+            buyer_token_account: pda::associated_token_account(&beneficiary, &config.token_mint, &token_program),
+            wallet_purchase: pda::wallet_purchase(&beneficiary).0,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            sale_stats: pda::sale_stats(&sale_config).0,
+            price_update,
+            system_program: system_program::ID,
+            token_program,
+            associated_token_program: associated_token::ID,
+            instructions: sysvar::instructions::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::BuyTokens { sol_amount },
+    )
+}
+
+/// Read-only: simulate it and decode the return data with
+/// `quote::buy_quote`. `config` is the current state of `sale_config`.
+pub fn quote_buy(
+    sale_config: Pubkey,
+    config: &SaleConfig,
+    token_program: Pubkey,
+    buyer: Pubkey,
+    price_update: Pubkey,
+    sol_amount: u64,
+) -> Instruction {
+    build(
+        accounts::QuoteBuy {
+            sale_config,
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            mint: config.token_mint,
+            program_token_account: pda::program_token_account(&sale_config, &config.token_mint, &token_program),
+            wallet_purchase: pda::wallet_purchase(&buyer).0,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            price_update,
+            token_program,
+        },
+        instruction::QuoteBuy { sol_amount, buyer },
+    )
+}
+
+/// Read-only: simulate it and decode the return data with
+/// `quote::wallet_status`. `config` is the current state of `sale_config`.
+pub fn wallet_status(sale_config: Pubkey, config: &SaleConfig, token_program: Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::WalletStatusView {
+            sale_config,
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            mint: config.token_mint,
+            program_token_account: pda::program_token_account(&sale_config, &config.token_mint, &token_program),
+            wallet_purchase: pda::wallet_purchase(&wallet).0,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            token_program,
+        },
+        instruction::WalletStatus { wallet },
+    )
+}
+
+fn admin_control(sale_config: Pubkey, authority: Pubkey) -> accounts::AdminControl {
+    accounts::AdminControl {
+        sale_config,
+        authority,
+        event_authority: pda::event_authority().0,
+        program: token_biu::ID,
+    }
+}
+
+pub fn change_recipient(sale_config: Pubkey, authority: Pubkey, new_recipient: Pubkey) -> Instruction {
+    build(
+        admin_control(sale_config, authority),
+        instruction::ChangeReciepentAccount {
+            new_receipent: new_recipient,
+        },
+    )
+}
+
+pub fn change_config_authority(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    build(
+        admin_control(sale_config, authority),
+        instruction::ChangeConfigAuthority { new_authority },
+    )
+}
+
+pub fn set_purchase_limit(sale_config: Pubkey, authority: Pubkey, new_limit: u64) -> Instruction {
+    build(
+        admin_control(sale_config, authority),
+        instruction::SetPurchaseLimit { new_limit },
+    )
+}
+
+/// Lock each purchase in the buyer's wallet for `lockup_duration` seconds;
+/// 0 turns lockups off for new purchases.
+pub fn set_lockup_duration(sale_config: Pubkey, authority: Pubkey, lockup_duration: i64) -> Instruction {
+    build(
+        admin_control(sale_config, authority),
+        instruction::SetLockupDuration { lockup_duration },
+    )
+}
+
+/// Sell by minting each purchase, up to `max_supply` total supply of `mint`,
+/// or from the pre-funded vault. Minting needs the sale authority PDA to be
+/// the mint authority.
+pub fn set_sale_mode(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    mint_on_purchase: bool,
+    max_supply: u64,
+) -> Instruction {
+    build(
+        accounts::SetSaleMode {
+            sale_config,
+            authority,
+            mint,
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::SetSaleMode {
+            mint_on_purchase,
+            max_supply,
+        },
+    )
+}
+
+/// Hand the mint authority the sale authority PDA holds for `mint` to
+/// `new_authority`, turning minting on purchase off if it is on.
+pub fn return_mint_authority(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    build(
+        accounts::ReturnMintAuthority {
+            sale_config,
+            authority,
+            mint,
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            token_program,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::ReturnMintAuthority { new_authority },
+    )
+}
+
+/// Purchases open at `start_time` and close at `end_time`, 0 for no end.
+pub fn set_sale_window(sale_config: Pubkey, authority: Pubkey, start_time: i64, end_time: i64) -> Instruction {
+    build(
+        admin_control(sale_config, authority),
+        instruction::SetSaleWindow { start_time, end_time },
+    )
+}
+
+/// Draft → Active.
+pub fn activate_sale(sale_config: Pubkey, authority: Pubkey) -> Instruction {
+    build(admin_control(sale_config, authority), instruction::ActivateSale {})
+}
+
+/// Active → Ended, before the end time.
+pub fn end_sale(sale_config: Pubkey, authority: Pubkey) -> Instruction {
+    build(admin_control(sale_config, authority), instruction::EndSale {})
+}
+
+/// Choose what `finalize_sale` does with the tokens left in the vault.
+pub fn set_unsold_policy(sale_config: Pubkey, authority: Pubkey, unsold_policy: UnsoldPolicy) -> Instruction {
+    build(
+        admin_control(sale_config, authority),
+        instruction::SetUnsoldPolicy { unsold_policy },
+    )
+}
+
+/// Ended → Finalized: return the vault for `mint` to the authority's ATA or
+/// burn it, per the unsold policy. Pass `with_schedule` when the sale has a
+/// schedule, so its last periods are closed out.
+pub fn finalize_sale(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    with_schedule: bool,
+) -> Instruction {
+    build(
+        accounts::FinalizeSale {
+            sale_config,
+            authority,
+            sale_stats: pda::sale_stats(&sale_config).0,
+            monthly_limits: with_schedule.then(|| pda::monthly_limits(&sale_config).0),
+            mint,
+            program_token_account: pda::program_token_account(&sale_config, &mint, &token_program),
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            treasury_token_account: pda::associated_token_account(&authority, &mint, &token_program),
+            token_program,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::FinalizeSale {},
+    )
+}
+
+/// Close a finalized sale's `SaleConfig`, its `SaleStats`, its empty vault and, when
+/// `with_schedule`, the schedule account, returning their rent to the
+/// authority.
+pub fn close_sale(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    with_schedule: bool,
+) -> Instruction {
+    build(
+        accounts::CloseSale {
+            sale_config,
+            authority,
+            sale_stats: pda::sale_stats(&sale_config).0,
+            monthly_limits: with_schedule.then(|| pda::monthly_limits(&sale_config).0),
+            mint,
+            program_token_account: pda::program_token_account(&sale_config, &mint, &token_program),
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            token_program,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::CloseSale {},
+    )
+}
+
+/// Close `buyer`'s `WalletPurchase`, returning its rent, once nothing is
+/// locked and `sale_config`, the sale it was last used in, is finalized or
+/// closed.
+pub fn close_wallet_purchase(buyer: Pubkey, sale_config: Pubkey) -> Instruction {
+    build(
+        accounts::CloseWalletPurchase {
+            buyer,
+            wallet_purchase: pda::wallet_purchase(&buyer).0,
+            sale_config,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::CloseWalletPurchase {},
+    )
+}
+
+/// Rewrite `wallet`'s `WalletPurchase` from an earlier program version in
+/// the current layout, `payer` topping up its rent; records already current
+/// are left alone. Permissionless, and needed before such a record can take
+/// another purchase.
+pub fn migrate_wallet_purchase(payer: Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateWalletPurchase {
+            wallet_purchase: pda::wallet_purchase(&wallet).0,
+            wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateWalletPurchase {},
+    )
+}
+
+/// Record that the end time has passed. Permissionless: any fee payer can
+/// send it.
+pub fn sync_sale_state(sale_config: Pubkey) -> Instruction {
+    build(
+        accounts::SyncSaleState {
+            sale_config,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::SyncSaleState {},
+    )
+}
+
+pub fn pause_sale(sale_config: Pubkey, authority: Pubkey) -> Instruction {
+    build(admin_control(sale_config, authority), instruction::PauseSale {})
+}
+
+pub fn resume_sale(sale_config: Pubkey, authority: Pubkey) -> Instruction {
+    build(admin_control(sale_config, authority), instruction::ResumeSale {})
+}
+
+/// Move `token_amount` of `mint` from the authority's ATA into the vault,
+/// recording it as deposited.
+pub fn fund_vault(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    token_amount: u64,
+) -> Instruction {
+    build(
+        accounts::FundVault {
+            sale_config,
+            authority,
+            mint,
+            funder_token_account: pda::associated_token_account(&authority, &mint, &token_program),
+            program_token_account: pda::program_token_account(&sale_config, &mint, &token_program),
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            token_program,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::FundVault { token_amount },
+    )
+}
+
+/// Create the schedule account with room for `periods` periods; `period`
+/// is the period length `generate_monthly_limits` uses.
+pub fn initialize_monthly_limits(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    periods: u16,
+    period: Period,
+) -> Instruction {
+    build(
+        accounts::InitializeMonthlyLimits {
+            sale_config,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeMonthlyLimits { periods, period },
+    )
+}
+
+/// Replace the whole schedule, resizing the account to fit. Only allowed
+/// before the current schedule starts; the tokens deposited in the vault for
+/// `mint` must cover everything it unlocks.
+pub fn set_monthly_limits(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    limits: Vec<u64>,
+    treasury_limits: Vec<u64>,
+    timestamps: Vec<i64>,
+) -> Instruction {
+    build(
+        accounts::SetMonthlyLimits {
+            sale_config,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            authority,
+            mint,
+            token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::SetMonthlyLimits {
+            limits,
+            treasury_limits,
+            timestamps,
+        },
+    )
+}
+
+/// Add future periods after the last one, keeping the schedule's accounting.
+pub fn append_monthly_limits(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    limits: Vec<u64>,
+    treasury_limits: Vec<u64>,
+    timestamps: Vec<i64>,
+) -> Instruction {
+    build(
+        accounts::AppendMonthlyLimits {
+            sale_config,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            authority,
+            mint,
+            token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::AppendMonthlyLimits {
+            limits,
+            treasury_limits,
+            timestamps,
+        },
+    )
+}
+
+/// Replace every period from `from_index` on; none of them may have been
+/// reached yet.
+#[allow(clippy::too_many_arguments)]
+pub fn amend_schedule(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    from_index: u16,
+    limits: Vec<u64>,
+    treasury_limits: Vec<u64>,
+    timestamps: Vec<i64>,
+) -> Instruction {
+    build(
+        accounts::AmendSchedule {
+            sale_config,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            authority,
+            mint,
+            token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::AmendSchedule {
+            from_index,
+            limits,
+            treasury_limits,
+            timestamps,
+        },
+    )
+}
+
+/// Generate and set the schedule on-chain, with the period length stored on
+/// the schedule account; the tokens deposited in the vault for `mint` must
+/// cover everything it unlocks.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_monthly_limits(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    start_time: i64,
+    period_unlock: u64,
+    treasury_unlock: u64,
+    bulk_unlock: u64,
+) -> Instruction {
+    build(
+        accounts::GenerateMonthlyLimits {
+            sale_config,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            authority,
+            mint,
+            token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::GenerateMonthlyLimits {
+            start_time,
+            period_unlock,
+            treasury_unlock,
+            bulk_unlock,
+        },
+    )
+}
+
+/// Unlock every period of `sale_config`'s schedule reached so far.
+/// Permissionless: any fee payer can send it.
+pub fn advance_schedule(sale_config: Pubkey) -> Instruction {
+    build(
+        accounts::AdvanceSchedule {
+            sale_config,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::AdvanceSchedule {},
+    )
+}
+
+/// Choose what happens to unsold sale allocations and after the final period.
+pub fn set_schedule_policy(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    carry_over: CarryOver,
+    tail_policy: TailPolicy,
+) -> Instruction {
+    build(
+        accounts::SetSchedulePolicy {
+            sale_config,
+            authority,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::SetSchedulePolicy {
+            carry_over,
+            tail_policy,
+        },
+    )
+}
+
+fn vesting_control(sale_config: Pubkey, authority: Pubkey) -> accounts::VestingControl {
+    accounts::VestingControl {
+        sale_config,
+        authority,
+        monthly_limits: pda::monthly_limits(&sale_config).0,
+        event_authority: pda::event_authority().0,
+        program: token_biu::ID,
+    }
+}
+
+pub fn enable_vesting(sale_config: Pubkey, authority: Pubkey) -> Instruction {
+    build(vesting_control(sale_config, authority), instruction::EnableVesting {})
+}
+
+pub fn disable_vesting(sale_config: Pubkey, authority: Pubkey) -> Instruction {
+    build(vesting_control(sale_config, authority), instruction::DisableVesting {})
+}
+
+/// Withdraw vault tokens to the authority's ATA for `mint`, out of the
+/// schedule's treasury allowance.
+pub fn withdraw_tokens(
+    sale_config: Pubkey,
+    authority: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    token_amount: u64,
+) -> Instruction {
+    build(
+        accounts::WithdrawTokens {
+            sale_config,
+            authority,
+            monthly_limits: pda::monthly_limits(&sale_config).0,
+            token_program,
+            program_token_account: pda::program_token_account(&sale_config, &mint, &token_program),
+            program_sale_authority: pda::sale_authority(&sale_config).0,
+            mint,
+            admin_token_account: pda::associated_token_account(&authority, &mint, &token_program),
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: token_biu::ID,
+        },
+        instruction::WithdrawTokens { token_amount },
+    )
+}
+
+/// Create the `token_biu_hook` extra-account list for `mint`, once per mint.
+pub fn initialize_transfer_hook(payer: Pubkey, mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: token_biu_hook::ID,
+        accounts: token_biu_hook::accounts::InitializeExtraAccountMetaList {
+            payer,
+            extra_account_meta_list: pda::extra_account_metas(&mint).0,
+            mint,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: token_biu_hook::instruction::InitializeExtraAccountMetaList {}.data(),
+    }
+}
+
+/// Accounts the `token_biu_hook` transfer hook needs for a transfer out of
+/// `source_owner`'s token account: the vault's owner, the sale authority,
+/// for `buy_tokens`, `withdraw_tokens` and `finalize_sale`, or the authority
+/// for `fund_vault`.
+pub fn transfer_hook_accounts(mint: &Pubkey, source_owner: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(token_biu_hook::ID, false),
+        AccountMeta::new_readonly(pda::extra_account_metas(mint).0, false),
+        AccountMeta::new_readonly(token_biu::ID, false),
+        AccountMeta::new_readonly(pda::wallet_purchase(source_owner).0, false),
+    ]
+}
@@ -0,0 +1,19 @@
+//! Typed client for the `token_biu` program: PDA derivation, instruction
+//! builders, account deserializers and purchase quotes, offline or from a
+//! simulated `quote_buy`, and wallet allowances from a simulated
+//! `wallet_status`. The program's `calendar` module is re-exported for
+//! computing UTC month starts exactly as the program does. Builders for the
+//! `token_biu_hook` transfer hook live alongside the sale's own.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use token_biu::{
+    calendar,
+    events,
+    states::{BuyQuote, CarryOver, Lock, MonthlyLimits, SaleConfig, SaleState, SaleStats, TailPolicy, UnsoldPolicy, WalletPurchase, WalletStatus},
+    ID,
+};
+pub use token_biu_hook::ID as HOOK_ID;
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use token_biu::constants::*;
+
+/// `SALE_AUTHORITY` PDA of `sale_config`, which owns its token vault and,
+/// in mint mode, the mint authority.
+pub fn sale_authority(sale_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SALE_AUTHORITY, sale_config.as_ref()], &token_biu::ID)
+}
+
+/// Anchor's event-CPI authority.
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY], &token_biu::ID)
+}
+
+/// Per-buyer `WalletPurchase` record.
+pub fn wallet_purchase(buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WALLET_PURCHASE, buyer.as_ref()], &token_biu::ID)
+}
+
+/// The `MonthlyLimits` vesting schedule of `sale_config`.
+pub fn monthly_limits(sale_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MONTHLY_LIMITS, sale_config.as_ref()], &token_biu::ID)
+}
+
+/// `SaleStats` purchase totals of `sale_config`.
+pub fn sale_stats(sale_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SALE_STATS, sale_config.as_ref()], &token_biu::ID)
+}
+
+/// Extra-account list of the `token_biu_hook` transfer hook for `mint`.
+pub fn extra_account_metas(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_biu_hook::constants::EXTRA_ACCOUNT_METAS, mint.as_ref()],
+        &token_biu_hook::ID,
+    )
+}
+
+/// Associated token account of `owner` for `mint`, owned by
+/// `token_program` (SPL Token or Token-2022).
+pub fn associated_token_account(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Token vault `sale_config` sells from: its sale authority's ATA.
+pub fn program_token_account(sale_config: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    associated_token_account(&sale_authority(sale_config).0, mint, token_program)
+}
@@ -0,0 +1,22 @@
+use anchor_lang::{AnchorDeserialize, Result};
+use token_biu::states::{BuyQuote, SaleConfig, WalletStatus};
+
+/// Tokens (in base units) `buy_tokens` would deliver for `sol_amount`
+/// lamports at `sol_price_usd`, using the program's own pricing.
+///
+/// Vault balance and purchase limits are not checked; simulate
+/// `instructions::quote_buy` and decode its return data with `buy_quote`
+/// for a quote that checks them too.
+pub fn token_amount(sale_config: &SaleConfig, sol_amount: u64, sol_price_usd: f64) -> u64 {
+    sale_config.token_amount(sol_amount, sol_price_usd)
+}
+
+/// Decode the return data of a simulated `quote_buy` instruction.
+pub fn buy_quote(return_data: &[u8]) -> Result<BuyQuote> {
+    Ok(BuyQuote::try_from_slice(return_data)?)
+}
+
+/// Decode the return data of a simulated `wallet_status` instruction.
+pub fn wallet_status(return_data: &[u8]) -> Result<WalletStatus> {
+    Ok(WalletStatus::try_from_slice(return_data)?)
+}
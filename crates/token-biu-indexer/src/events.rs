@@ -0,0 +1,379 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use token_biu::events::*;
+
+use crate::legacy;
+
+/// A decoded `token_biu` event.
+pub enum ProgramEvent {
+    SaleInitialized(SaleInitialized),
+    TokensPurchased(TokensPurchased),
+    RecipientChanged(RecipientChanged),
+    TokenAuthorityChanged(TokenAuthorityChanged),
+    WalletLimitSet(WalletLimitSet),
+    MonthlyLimitsSet(MonthlyLimitsSet),
+    MonthlyLimitsAppended(MonthlyLimitsAppended),
+    ScheduleAmended(ScheduleAmended),
+    VestingEnabled(VestingEnabled),
+    VestingDisabled(VestingDisabled),
+    AdminWithdrawnTokens(AdminWithdrawnTokens),
+    SchedulePolicySet(SchedulePolicySet),
+    PeriodClosed(PeriodClosed),
+    PeriodUnlocked(PeriodUnlocked),
+    UnsoldAllocationExpired(UnsoldAllocationExpired),
+    UnsoldAllocationBurned(UnsoldAllocationBurned),
+    SaleAllowanceUsed(SaleAllowanceUsed),
+    TreasuryAllowanceUsed(TreasuryAllowanceUsed),
+    LockupDurationSet(LockupDurationSet),
+    TokensLocked(TokensLocked),
+    SaleModeSet(SaleModeSet),
+    MintAuthorityReturned(MintAuthorityReturned),
+    TokensMinted(TokensMinted),
+    SaleWindowSet(SaleWindowSet),
+    SaleStateChanged(SaleStateChanged),
+    UnsoldPolicySet(UnsoldPolicySet),
+    SaleFinalized(SaleFinalized),
+    SaleClosed(SaleClosed),
+    WalletPurchaseClosed(WalletPurchaseClosed),
+    VaultFunded(VaultFunded),
+}
+
+impl ProgramEvent {
+    /// Decode an event from its discriminator-prefixed Borsh payload.
+    ///
+    /// Returns `None` for payloads that don't belong to one of our events,
+    /// and an error for one of ours that doesn't decode, so it isn't lost.
+    /// Events whose layout changed are tried against the current layout
+    /// first and then the first release's layout in `legacy`; every layout
+    /// must consume the whole payload.
+    pub fn decode(data: &[u8]) -> Result<Option<Self>> {
+        if data.len() < 8 {
+            return Ok(None);
+        }
+        let (discriminator, body) = data.split_at(8);
+
+        macro_rules! decode {
+            ($($event:ident $(| $legacy:ident)*),* $(,)?) => {
+                $(
+                    if discriminator == $event::DISCRIMINATOR {
+                        let event = $event::try_from_slice(body)
+                            $(.or_else(|_| legacy::$legacy::try_from_slice(body).map($event::from)))*
+                            .with_context(|| format!("decoding {}", stringify!($event)))?;
+                        return Ok(Some(ProgramEvent::$event(event)));
+                    }
+                )*
+            };
+        }
+
+        decode!(
+            SaleInitialized,
+            TokensPurchased | TokensPurchasedV1,
+            RecipientChanged,
+            TokenAuthorityChanged,
+            WalletLimitSet,
+            MonthlyLimitsSet | MonthlyLimitsSetV1,
+            MonthlyLimitsAppended,
+            ScheduleAmended,
+            VestingEnabled,
+            VestingDisabled,
+            AdminWithdrawnTokens | AdminWithdrawnTokensV1,
+            SchedulePolicySet,
+            PeriodClosed,
+            PeriodUnlocked,
+            UnsoldAllocationExpired,
+            UnsoldAllocationBurned,
+            SaleAllowanceUsed,
+            TreasuryAllowanceUsed,
+            LockupDurationSet,
+            TokensLocked,
+            SaleModeSet,
+            MintAuthorityReturned,
+            TokensMinted,
+            SaleWindowSet,
+            SaleStateChanged,
+            UnsoldPolicySet,
+            SaleFinalized,
+            SaleClosed,
+            WalletPurchaseClosed,
+            VaultFunded,
+        );
+
+        Ok(None)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgramEvent::SaleInitialized(_) => "SaleInitialized",
+            ProgramEvent::TokensPurchased(_) => "TokensPurchased",
+            ProgramEvent::RecipientChanged(_) => "RecipientChanged",
+            ProgramEvent::TokenAuthorityChanged(_) => "TokenAuthorityChanged",
+            ProgramEvent::WalletLimitSet(_) => "WalletLimitSet",
+            ProgramEvent::MonthlyLimitsSet(_) => "MonthlyLimitsSet",
+            ProgramEvent::MonthlyLimitsAppended(_) => "MonthlyLimitsAppended",
+            ProgramEvent::ScheduleAmended(_) => "ScheduleAmended",
+            ProgramEvent::VestingEnabled(_) => "VestingEnabled",
+            ProgramEvent::VestingDisabled(_) => "VestingDisabled",
+            ProgramEvent::AdminWithdrawnTokens(_) => "AdminWithdrawnTokens",
+            ProgramEvent::SchedulePolicySet(_) => "SchedulePolicySet",
+            ProgramEvent::PeriodClosed(_) => "PeriodClosed",
+            ProgramEvent::PeriodUnlocked(_) => "PeriodUnlocked",
+            ProgramEvent::UnsoldAllocationExpired(_) => "UnsoldAllocationExpired",
+            ProgramEvent::UnsoldAllocationBurned(_) => "UnsoldAllocationBurned",
+            ProgramEvent::SaleAllowanceUsed(_) => "SaleAllowanceUsed",
+            ProgramEvent::TreasuryAllowanceUsed(_) => "TreasuryAllowanceUsed",
+            ProgramEvent::LockupDurationSet(_) => "LockupDurationSet",
+            ProgramEvent::TokensLocked(_) => "TokensLocked",
+            ProgramEvent::SaleModeSet(_) => "SaleModeSet",
+            ProgramEvent::MintAuthorityReturned(_) => "MintAuthorityReturned",
+            ProgramEvent::TokensMinted(_) => "TokensMinted",
+            ProgramEvent::SaleWindowSet(_) => "SaleWindowSet",
+            ProgramEvent::SaleStateChanged(_) => "SaleStateChanged",
+            ProgramEvent::UnsoldPolicySet(_) => "UnsoldPolicySet",
+            ProgramEvent::SaleFinalized(_) => "SaleFinalized",
+            ProgramEvent::SaleClosed(_) => "SaleClosed",
+            ProgramEvent::WalletPurchaseClosed(_) => "WalletPurchaseClosed",
+            ProgramEvent::VaultFunded(_) => "VaultFunded",
+        }
+    }
+
+    /// JSON payload stored alongside every event in the ledger.
+    pub fn to_json(&self) -> Value {
+        match self {
+            ProgramEvent::SaleInitialized(e) => json!({
+                "authority": e.authority.to_string(),
+                "token_price": e.token_price,
+                "recipient": e.recipient.to_string(),
+            }),
+            ProgramEvent::TokensPurchased(e) => json!({
+                "buyer": e.buyer.to_string(),
+                "payer": e.payer.to_string(),
+                "caller_program": e.caller_program.map(|program| program.to_string()),
+                "beneficiary": e.beneficiary.to_string(),
+                "sol_amount": e.sol_amount,
+                "token_amount": e.token_amount,
+                "sol_price": e.sol_price,
+                "transfer_fee": e.transfer_fee,
+            }),
+            ProgramEvent::RecipientChanged(e) => json!({
+                "old_recipient": e.old_recipient.to_string(),
+                "new_recipient": e.new_recipient.to_string(),
+            }),
+            ProgramEvent::TokenAuthorityChanged(e) => json!({
+                "old_authority": e.old_authority.to_string(),
+                "new_authority": e.new_authority.to_string(),
+            }),
+            ProgramEvent::WalletLimitSet(e) => json!({
+                "new_limit": e.new_limit,
+            }),
+            ProgramEvent::MonthlyLimitsSet(e) => json!({
+                "limits": e.limits,
+                "treasury_limits": e.treasury_limits,
+                "timestamps": e.timestamps,
+            }),
+            ProgramEvent::MonthlyLimitsAppended(e) => json!({
+                "limits": e.limits,
+                "treasury_limits": e.treasury_limits,
+                "timestamps": e.timestamps,
+                "total_periods": e.total_periods,
+            }),
+            ProgramEvent::ScheduleAmended(e) => json!({
+                "from_index": e.from_index,
+                "limits": e.limits,
+                "treasury_limits": e.treasury_limits,
+                "timestamps": e.timestamps,
+                "total_periods": e.total_periods,
+            }),
+            ProgramEvent::VestingEnabled(e) => json!({
+                "vesting": e.vesting,
+            }),
+            ProgramEvent::VestingDisabled(e) => json!({
+                "vesting": e.vesting,
+            }),
+            ProgramEvent::AdminWithdrawnTokens(e) => json!({
+                "tokens_withdrawn": e.tokens_withdrawn,
+                "transfer_fee": e.transfer_fee,
+            }),
+            ProgramEvent::SchedulePolicySet(e) => json!({
+                "carry_over": format!("{:?}", e.carry_over),
+                "tail_policy": format!("{:?}", e.tail_policy),
+            }),
+            ProgramEvent::PeriodClosed(e) => json!({
+                "period_index": e.period_index,
+                "tokens_sold": e.tokens_sold,
+                "sol_raised": e.sol_raised,
+            }),
+            ProgramEvent::PeriodUnlocked(e) => json!({
+                "period_index": e.period_index,
+                "timestamp": e.timestamp,
+                "sale_limit": e.sale_limit,
+                "treasury_limit": e.treasury_limit,
+            }),
+            ProgramEvent::UnsoldAllocationExpired(e) => json!({
+                "token_amount": e.token_amount,
+            }),
+            ProgramEvent::UnsoldAllocationBurned(e) => json!({
+                "token_amount": e.token_amount,
+            }),
+            ProgramEvent::SaleAllowanceUsed(e) => json!({
+                "token_amount": e.token_amount,
+                "sale_available": e.sale_available,
+                "tokens_sold": e.tokens_sold,
+            }),
+            ProgramEvent::TreasuryAllowanceUsed(e) => json!({
+                "token_amount": e.token_amount,
+                "treasury_available": e.treasury_available,
+                "tokens_withdrawn": e.tokens_withdrawn,
+            }),
+            ProgramEvent::LockupDurationSet(e) => json!({
+                "lockup_duration": e.lockup_duration,
+            }),
+            ProgramEvent::TokensLocked(e) => json!({
+                "buyer": e.buyer.to_string(),
+                "token_amount": e.token_amount,
+                "locked_amount": e.locked_amount,
+                "unlock_timestamp": e.unlock_timestamp,
+            }),
+            ProgramEvent::SaleModeSet(e) => json!({
+                "mint_on_purchase": e.mint_on_purchase,
+                "max_supply": e.max_supply,
+            }),
+            ProgramEvent::MintAuthorityReturned(e) => json!({
+                "new_authority": e.new_authority.to_string(),
+            }),
+            ProgramEvent::TokensMinted(e) => json!({
+                "buyer": e.buyer.to_string(),
+                "token_amount": e.token_amount,
+                "tokens_minted": e.tokens_minted,
+                "supply": e.supply,
+            }),
+            ProgramEvent::SaleWindowSet(e) => json!({
+                "start_time": e.start_time,
+                "end_time": e.end_time,
+            }),
+            ProgramEvent::SaleStateChanged(e) => json!({
+                "old_state": format!("{:?}", e.old_state),
+                "new_state": format!("{:?}", e.new_state),
+                "timestamp": e.timestamp,
+            }),
+            ProgramEvent::UnsoldPolicySet(e) => json!({
+                "unsold_policy": format!("{:?}", e.unsold_policy),
+            }),
+            ProgramEvent::SaleFinalized(e) => json!({
+                "sol_raised": e.sol_raised,
+                "tokens_sold": e.tokens_sold,
+                "tokens_minted": e.tokens_minted,
+                "unsold_tokens": e.unsold_tokens,
+                "unsold_policy": format!("{:?}", e.unsold_policy),
+                "transfer_fee": e.transfer_fee,
+            }),
+            ProgramEvent::SaleClosed(e) => json!({
+                "sale_config": e.sale_config.to_string(),
+                "lamports_reclaimed": e.lamports_reclaimed,
+            }),
+            ProgramEvent::WalletPurchaseClosed(e) => json!({
+                "wallet": e.wallet.to_string(),
+                "total_purchased": e.total_purchased,
+            }),
+            ProgramEvent::VaultFunded(e) => json!({
+                "funder": e.funder.to_string(),
+                "token_amount": e.token_amount,
+                "transfer_fee": e.transfer_fee,
+                "tokens_deposited": e.tokens_deposited,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, AnchorSerialize, Event};
+
+    use super::*;
+    use crate::legacy::*;
+
+    /// `event` serialized under the discriminator of `E`.
+    fn payload<E: Discriminator>(event: impl AnchorSerialize) -> Vec<u8> {
+        let mut data = E::DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        data
+    }
+
+    fn decode(data: &[u8]) -> ProgramEvent {
+        ProgramEvent::decode(data).unwrap().unwrap()
+    }
+
+    #[test]
+    fn decodes_the_current_layouts() {
+        let event = TokensPurchased {
+            buyer: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            caller_program: Some(Pubkey::new_unique()),
+            sol_amount: 1_000_000_000,
+            token_amount: 40_000_000,
+            sol_price: 200.0,
+            transfer_fee: 5,
+        };
+
+        let ProgramEvent::TokensPurchased(decoded) = decode(&event.data()) else {
+            panic!("not a purchase");
+        };
+        assert_eq!(decoded.beneficiary, event.beneficiary);
+        assert_eq!(decoded.caller_program, event.caller_program);
+        assert_eq!(decoded.transfer_fee, 5);
+    }
+
+    #[test]
+    fn decodes_legacy_purchases() {
+        let buyer = Pubkey::new_unique();
+        let data = payload::<TokensPurchased>(TokensPurchasedV1 {
+            buyer,
+            sol_amount: 1_000,
+            token_amount: 40,
+            sol_price: 200.0,
+        });
+
+        let ProgramEvent::TokensPurchased(decoded) = decode(&data) else {
+            panic!("not a purchase");
+        };
+        assert_eq!((decoded.buyer, decoded.payer, decoded.beneficiary), (buyer, buyer, buyer));
+        assert_eq!(decoded.caller_program, None);
+        assert_eq!((decoded.sol_amount, decoded.token_amount), (1_000, 40));
+        assert_eq!((decoded.sol_price, decoded.transfer_fee), (200.0, 0));
+    }
+
+    #[test]
+    fn decodes_legacy_schedules_and_withdrawals() {
+        let data = payload::<MonthlyLimitsSet>(MonthlyLimitsSetV1 {
+            limits: [7; 14],
+            timestamps: std::array::from_fn(|index| index as i64),
+        });
+        let ProgramEvent::MonthlyLimitsSet(decoded) = decode(&data) else {
+            panic!("not a schedule");
+        };
+        assert_eq!(decoded.limits, [7; 14]);
+        assert_eq!(decoded.treasury_limits, [0; 14]);
+        assert_eq!(decoded.timestamps, (0..14).collect::<Vec<i64>>());
+
+        let data = payload::<AdminWithdrawnTokens>(AdminWithdrawnTokensV1 {
+            tokens_withdrawn: 9,
+        });
+        let ProgramEvent::AdminWithdrawnTokens(decoded) = decode(&data) else {
+            panic!("not a withdrawal");
+        };
+        assert_eq!((decoded.tokens_withdrawn, decoded.transfer_fee), (9, 0));
+    }
+
+    #[test]
+    fn payloads_matching_no_layout_are_errors() {
+        let mut data = payload::<AdminWithdrawnTokens>(AdminWithdrawnTokensV1 {
+            tokens_withdrawn: 9,
+        });
+        data.push(0);
+
+        assert!(ProgramEvent::decode(&data).is_err());
+        assert!(ProgramEvent::decode(&TokensPurchased::DISCRIMINATOR[..]).is_err());
+        assert!(ProgramEvent::decode(&[0; 16]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,331 @@
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::{events::ProgramEvent, transaction::EncodedTransaction};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature   TEXT PRIMARY KEY,
+    slot        INTEGER NOT NULL,
+    block_time  INTEGER,
+    event_count INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    signature   TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    block_time  INTEGER,
+    name        TEXT NOT NULL,
+    payload     TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS purchases (
+    signature    TEXT NOT NULL,
+    event_index  INTEGER NOT NULL,
+    slot         INTEGER NOT NULL,
+    block_time   INTEGER,
+    buyer        TEXT NOT NULL,
+    sol_amount   INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    sol_price    REAL NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS buyers (
+    wallet         TEXT PRIMARY KEY,
+    purchase_count INTEGER NOT NULL,
+    total_lamports INTEGER NOT NULL,
+    total_tokens   INTEGER NOT NULL,
+    first_slot     INTEGER NOT NULL,
+    last_slot      INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature   TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    block_time  INTEGER,
+    amount      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS purchases_buyer ON purchases (buyer);
+";
+
+/// Outcome of feeding one transaction to the ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ingested {
+    /// Newly recorded, with this many events.
+    Recorded(usize),
+    /// Already in the ledger; nothing was written.
+    Duplicate,
+    /// Failed on-chain; nothing was written.
+    Failed,
+}
+
+pub struct Ledger {
+    conn: Connection,
+    program_id: Pubkey,
+}
+
+impl Ledger {
+    pub fn open(path: &Path, program_id: Pubkey) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("opening ledger {}", path.display()))?;
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self { conn, program_id })
+    }
+
+    pub fn contains(&self, signature: &str) -> Result<bool> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM transactions WHERE signature = ?1",
+                params![signature],
+                |_| Ok(()),
+            )
+            .optional()?;
+
+        Ok(found.is_some())
+    }
+
+    /// Record a transaction and everything derived from its events.
+    ///
+    /// Re-ingesting a transaction that is already in the ledger is a no-op,
+    /// so dumps and RPC ranges can overlap freely.
+    pub fn ingest(&mut self, tx: &EncodedTransaction) -> Result<Ingested> {
+        let signature = tx.signature()?;
+
+        if !tx.succeeded() {
+            return Ok(Ingested::Failed);
+        }
+        if self.contains(signature)? {
+            return Ok(Ingested::Duplicate);
+        }
+
+        // Nothing is recorded when an event fails to decode, so the
+        // transaction is picked up again on the next run
+        let events = tx
+            .events(&self.program_id)
+            .with_context(|| format!("decoding events of {signature}"))?;
+        let db = self.conn.transaction()?;
+
+        db.execute(
+            "INSERT INTO transactions (signature, slot, block_time, event_count)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![signature, to_sql(tx.slot)?, tx.block_time, events.len()],
+        )?;
+
+        for (index, event) in events.iter().enumerate() {
+            record_event(&db, tx, signature, index, event)?;
+        }
+
+        db.commit()?;
+
+        Ok(Ingested::Recorded(events.len()))
+    }
+}
+
+fn record_event(
+    db: &Transaction,
+    tx: &EncodedTransaction,
+    signature: &str,
+    index: usize,
+    event: &ProgramEvent,
+) -> Result<()> {
+    let slot = to_sql(tx.slot)?;
+
+    db.execute(
+        "INSERT INTO events (signature, event_index, slot, block_time, name, payload)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            signature,
+            index,
+            slot,
+            tx.block_time,
+            event.name(),
+            event.to_json().to_string()
+        ],
+    )?;
+
+    match event {
+        ProgramEvent::TokensPurchased(purchase) => {
+            // Keyed by who received the tokens and was charged the limits
+            let buyer = purchase.beneficiary.to_string();
+            let sol_amount = to_sql(purchase.sol_amount)?;
+            let token_amount = to_sql(purchase.token_amount)?;
+
+            db.execute(
+                "INSERT INTO purchases
+                    (signature, event_index, slot, block_time, buyer, sol_amount, token_amount, sol_price)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    tx.block_time,
+                    buyer,
+                    sol_amount,
+                    token_amount,
+                    purchase.sol_price
+                ],
+            )?;
+
+            db.execute(
+                "INSERT INTO buyers
+                    (wallet, purchase_count, total_lamports, total_tokens, first_slot, last_slot)
+                 VALUES (?1, 1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT (wallet) DO UPDATE SET
+                    purchase_count = purchase_count + 1,
+                    total_lamports = total_lamports + excluded.total_lamports,
+                    total_tokens   = total_tokens + excluded.total_tokens,
+                    first_slot     = MIN(first_slot, excluded.first_slot),
+                    last_slot      = MAX(last_slot, excluded.last_slot)",
+                params![buyer, sol_amount, token_amount, slot],
+            )?;
+        }
+        ProgramEvent::AdminWithdrawnTokens(withdrawal) => {
+            db.execute(
+                "INSERT INTO withdrawals (signature, event_index, slot, block_time, amount)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    tx.block_time,
+                    to_sql(withdrawal.tokens_withdrawn)?
+                ],
+            )?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// SQLite integers are signed 64-bit.
+fn to_sql(value: u64) -> Result<i64> {
+    i64::try_from(value).with_context(|| format!("{value} does not fit in a SQLite integer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Event;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde_json::json;
+    use token_biu::events::TokensPurchased;
+
+    use super::*;
+    use crate::transaction::parse_dump;
+
+    const SIGNATURE: &str = "5j7s6NiJS3JAkvgkoc18WVAsiSaci2pxB2A6ueCJP4tprA2TFg9wSyTLeYouxPBJEMzJinENTkpA52YStRW5Dia7";
+
+    fn ledger() -> Ledger {
+        Ledger::open(Path::new(":memory:"), token_biu::ID).unwrap()
+    }
+
+    fn purchase() -> TokensPurchased {
+        TokensPurchased {
+            buyer: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            caller_program: None,
+            sol_amount: 1_000_000_000,
+            token_amount: 40_000_000,
+            sol_price: 200.0,
+            transfer_fee: 0,
+        }
+    }
+
+    /// A dump of one transaction whose logs carry `payload` as program data.
+    fn dump(payload: &[u8]) -> String {
+        let program_id = token_biu::ID.to_string();
+
+        json!({
+            "slot": 42,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "signatures": [SIGNATURE],
+                "message": { "accountKeys": [program_id] },
+            },
+            "meta": {
+                "err": null,
+                "logMessages": [
+                    format!("Program {program_id} invoke [1]"),
+                    format!("Program data: {}", STANDARD.encode(payload)),
+                    format!("Program {program_id} success"),
+                ],
+            },
+        })
+        .to_string()
+    }
+
+    fn count(ledger: &Ledger, table: &str) -> i64 {
+        ledger
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn ingesting_the_same_dump_twice_records_it_once() {
+        let mut ledger = ledger();
+        let purchase = purchase();
+        let dump = dump(&purchase.data());
+
+        for expected in [Ingested::Recorded(1), Ingested::Duplicate] {
+            for tx in parse_dump(&dump).unwrap() {
+                assert_eq!(ledger.ingest(&tx).unwrap(), expected);
+            }
+        }
+
+        assert_eq!(count(&ledger, "transactions"), 1);
+        assert_eq!(count(&ledger, "events"), 1);
+        assert_eq!(count(&ledger, "purchases"), 1);
+
+        let (purchase_count, total_tokens): (i64, i64) = ledger
+            .conn
+            .query_row(
+                "SELECT purchase_count, total_tokens FROM buyers WHERE wallet = ?1",
+                params![purchase.beneficiary.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(purchase_count, 1);
+        assert_eq!(total_tokens, 40_000_000);
+    }
+
+    #[test]
+    fn overlapping_dumps_are_recorded_once() {
+        let mut ledger = ledger();
+        let dump = dump(&purchase().data());
+        let both = format!("[{dump}, {dump}]");
+
+        let ingested: Vec<Ingested> = parse_dump(&both)
+            .unwrap()
+            .iter()
+            .map(|tx| ledger.ingest(tx).unwrap())
+            .collect();
+
+        assert_eq!(ingested, [Ingested::Recorded(1), Ingested::Duplicate]);
+        assert_eq!(count(&ledger, "purchases"), 1);
+    }
+
+    #[test]
+    fn undecodable_event_leaves_the_transaction_for_a_retry() {
+        let mut ledger = ledger();
+        let mut payload = purchase().data();
+        payload.truncate(payload.len() - 4);
+        let tx = parse_dump(&dump(&payload)).unwrap().remove(0);
+
+        assert!(ledger.ingest(&tx).is_err());
+        assert!(!ledger.contains(SIGNATURE).unwrap());
+        assert_eq!(count(&ledger, "events"), 0);
+    }
+}
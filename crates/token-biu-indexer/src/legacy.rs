@@ -0,0 +1,65 @@
+//! Layouts earlier program versions emitted under the same event names
+//! (and so the same discriminators), converted to the current events with
+//! defaults for the fields they didn't have.
+
+use anchor_lang::prelude::*;
+use token_biu::events::{AdminWithdrawnTokens, MonthlyLimitsSet, TokensPurchased};
+
+/// Number of periods in the original fixed-size schedule.
+const FIXED_PERIODS: usize = 14;
+
+/// `TokensPurchased` before Token-2022 support added `transfer_fee`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TokensPurchasedV1 {
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub sol_price: f64,
+}
+
+/// `MonthlyLimitsSet` of the original fixed 14-period schedule.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MonthlyLimitsSetV1 {
+    pub limits: [u64; FIXED_PERIODS],
+    pub timestamps: [i64; FIXED_PERIODS],
+}
+
+/// `AdminWithdrawnTokens` before Token-2022 support added `transfer_fee`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AdminWithdrawnTokensV1 {
+    pub tokens_withdrawn: u64,
+}
+
+impl From<TokensPurchasedV1> for TokensPurchased {
+    fn from(event: TokensPurchasedV1) -> Self {
+        TokensPurchased {
+            buyer: event.buyer,
+            payer: event.buyer,
+            beneficiary: event.buyer,
+            caller_program: None,
+            sol_amount: event.sol_amount,
+            token_amount: event.token_amount,
+            sol_price: event.sol_price,
+            transfer_fee: 0,
+        }
+    }
+}
+
+impl From<MonthlyLimitsSetV1> for MonthlyLimitsSet {
+    fn from(event: MonthlyLimitsSetV1) -> Self {
+        MonthlyLimitsSet {
+            limits: event.limits.to_vec(),
+            treasury_limits: vec![0; FIXED_PERIODS],
+            timestamps: event.timestamps.to_vec(),
+        }
+    }
+}
+
+impl From<AdminWithdrawnTokensV1> for AdminWithdrawnTokens {
+    fn from(event: AdminWithdrawnTokensV1) -> Self {
+        AdminWithdrawnTokens {
+            tokens_withdrawn: event.tokens_withdrawn,
+            transfer_fee: 0,
+        }
+    }
+}
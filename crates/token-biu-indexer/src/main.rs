@@ -0,0 +1,113 @@
+mod events;
+mod ledger;
+mod legacy;
+mod rpc;
+mod transaction;
+
+use std::{fs, path::PathBuf, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+use ledger::{Ingested, Ledger};
+use rpc::RpcClient;
+
+/// Build a local SQLite ledger of purchases, buyers and withdrawals from
+/// token_biu program events.
+#[derive(Parser)]
+#[command(name = "token-biu-indexer", version)]
+struct Cli {
+    /// SQLite ledger to create or update
+    #[arg(long, default_value = "token_biu_ledger.sqlite")]
+    db: PathBuf,
+
+    /// Program id whose events are indexed
+    #[arg(long, default_value_t = token_biu::ID.to_string())]
+    program_id: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Ingest transactions dumped from `getTransaction` (`"encoding": "json"`)
+    IngestFile {
+        /// Files holding a transaction, an array of transactions or RPC responses
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Ingest the program's history from an RPC node, newest transactions
+    /// back to the last one already in the ledger
+    IngestRpc {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+
+        /// Ingest at most this many new transactions, oldest first
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Default)]
+struct Summary {
+    recorded: usize,
+    events: usize,
+    duplicates: usize,
+    failed: usize,
+}
+
+impl Summary {
+    fn add(&mut self, ingested: Ingested) {
+        match ingested {
+            Ingested::Recorded(events) => {
+                self.recorded += 1;
+                self.events += events;
+            }
+            Ingested::Duplicate => self.duplicates += 1,
+            Ingested::Failed => self.failed += 1,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let program_id = Pubkey::from_str(&cli.program_id).context("invalid --program-id")?;
+    let mut ledger = Ledger::open(&cli.db, program_id)?;
+    let mut summary = Summary::default();
+
+    match cli.command {
+        Command::IngestFile { files } => {
+            for file in files {
+                let contents = fs::read_to_string(&file)
+                    .with_context(|| format!("reading {}", file.display()))?;
+                let transactions = transaction::parse_dump(&contents)
+                    .with_context(|| format!("parsing {}", file.display()))?;
+
+                for tx in &transactions {
+                    summary.add(ledger.ingest(tx)?);
+                }
+            }
+        }
+        Command::IngestRpc { url, limit } => {
+            let client = RpcClient::new(url);
+            let signatures = client.unindexed_signatures(&cli.program_id, limit, |signature| {
+                ledger.contains(signature)
+            })?;
+
+            for signature in &signatures {
+                if let Some(tx) = client.transaction(signature)? {
+                    summary.add(ledger.ingest(&tx)?);
+                }
+            }
+        }
+    }
+
+    println!(
+        "recorded {} transactions ({} events), skipped {} already indexed and {} failed",
+        summary.recorded, summary.events, summary.duplicates, summary.failed
+    );
+
+    Ok(())
+}
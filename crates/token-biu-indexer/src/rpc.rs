@@ -0,0 +1,160 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::transaction::EncodedTransaction;
+
+/// Signatures requested per `getSignaturesForAddress` page (the RPC maximum).
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Minimal blocking JSON-RPC client, enough to page through the program's
+/// transaction history on a local validator or any RPC node.
+pub struct RpcClient {
+    url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureInfo {
+    signature: String,
+    err: Option<Value>,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))?
+            .into_json()?;
+
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    /// Successful signatures for `address` that `known` doesn't have yet,
+    /// oldest first and at most `limit` of them. Pages back through the
+    /// whole history, so a limited run takes the oldest missing transactions
+    /// and the next run carries on after them.
+    pub fn unindexed_signatures(
+        &self,
+        address: &str,
+        limit: Option<usize>,
+        known: impl FnMut(&str) -> Result<bool>,
+    ) -> Result<Vec<String>> {
+        let mut history = Vec::new();
+        let mut before: Option<String> = None;
+
+        loop {
+            let mut config = json!({ "limit": SIGNATURE_PAGE_SIZE, "commitment": "confirmed" });
+            if let Some(before) = &before {
+                config["before"] = json!(before);
+            }
+
+            let page: Vec<SignatureInfo> =
+                serde_json::from_value(self.call("getSignaturesForAddress", json!([address, config]))?)?;
+            let page_len = page.len();
+
+            before = page.last().map(|info| info.signature.clone());
+            history.extend(page);
+
+            if page_len < SIGNATURE_PAGE_SIZE {
+                return oldest_unindexed(history, limit, known);
+            }
+        }
+    }
+
+    pub fn transaction(&self, signature: &str) -> Result<Option<EncodedTransaction>> {
+        let result = self.call(
+            "getTransaction",
+            json!([
+                signature,
+                {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0,
+                }
+            ]),
+        )?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_value(result)?))
+    }
+}
+
+/// The successful signatures of `history`, listed newest first, that
+/// `known` doesn't have, oldest first and at most `limit` of them.
+fn oldest_unindexed(
+    history: Vec<SignatureInfo>,
+    limit: Option<usize>,
+    mut known: impl FnMut(&str) -> Result<bool>,
+) -> Result<Vec<String>> {
+    let mut signatures = Vec::new();
+
+    for info in history.into_iter().rev() {
+        if limit.is_some_and(|limit| signatures.len() >= limit) {
+            break;
+        }
+        if info.err.is_none() && !known(&info.signature)? {
+            signatures.push(info.signature);
+        }
+    }
+
+    Ok(signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// Newest-first history of successful signatures "9" down to "0"
+    fn history() -> Vec<SignatureInfo> {
+        (0..10)
+            .rev()
+            .map(|index| SignatureInfo { signature: index.to_string(), err: None })
+            .collect()
+    }
+
+    #[test]
+    fn limited_runs_work_forward_from_the_oldest_transaction() {
+        let mut ledger = HashSet::new();
+
+        for expected in [["0", "1", "2", "3"], ["4", "5", "6", "7"]] {
+            let signatures = oldest_unindexed(history(), Some(4), |s| Ok(ledger.contains(s))).unwrap();
+            assert_eq!(signatures, expected);
+            ledger.extend(signatures);
+        }
+
+        let signatures = oldest_unindexed(history(), Some(4), |s| Ok(ledger.contains(s))).unwrap();
+        assert_eq!(signatures, ["8", "9"]);
+    }
+
+    #[test]
+    fn gaps_and_failed_transactions_are_handled() {
+        let mut history = history();
+        history[4].err = Some(json!("InstructionError"));
+        let ledger: HashSet<_> = ["9", "2"].map(String::from).into();
+
+        let signatures = oldest_unindexed(history, None, |s| Ok(ledger.contains(s))).unwrap();
+
+        assert_eq!(signatures, ["0", "1", "3", "4", "6", "7", "8"]);
+    }
+}
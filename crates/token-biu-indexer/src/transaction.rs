@@ -0,0 +1,207 @@
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+
+use crate::events::ProgramEvent;
+
+/// A transaction as returned by `getTransaction` with `"encoding": "json"`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transaction: TransactionBody,
+    pub meta: Option<TransactionMeta>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionBody {
+    pub signatures: Vec<String>,
+    pub message: Message,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub account_keys: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMeta {
+    pub err: Option<serde_json::Value>,
+    #[serde(default)]
+    pub inner_instructions: Option<Vec<InnerInstructions>>,
+    #[serde(default)]
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default)]
+    pub loaded_addresses: Option<LoadedAddresses>,
+}
+
+#[derive(Deserialize)]
+pub struct InnerInstructions {
+    pub index: u8,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompiledInstruction {
+    pub program_id_index: usize,
+    pub data: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct LoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+impl EncodedTransaction {
+    pub fn signature(&self) -> Result<&str> {
+        self.transaction
+            .signatures
+            .first()
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("transaction at slot {} has no signatures", self.slot))
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.meta.as_ref().is_some_and(|meta| meta.err.is_none())
+    }
+
+    /// Decode every `token_biu` event in the transaction, in execution order.
+    ///
+    /// Events are read from the self-CPI inner instructions. Transactions
+    /// from before events were emitted through CPI only carry them in the
+    /// `Program data:` logs, so those are used as a fallback.
+    ///
+    /// Fails if one of the program's events doesn't decode.
+    pub fn events(&self, program_id: &Pubkey) -> Result<Vec<ProgramEvent>> {
+        let events = self.cpi_events(program_id)?;
+        if !events.is_empty() {
+            return Ok(events);
+        }
+
+        self.log_events(program_id)
+    }
+
+    /// Static keys followed by the lookup-table keys of a v0 transaction.
+    fn account_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .transaction
+            .message
+            .account_keys
+            .iter()
+            .map(String::as_str)
+            .collect();
+
+        if let Some(loaded) = self.meta.as_ref().and_then(|meta| meta.loaded_addresses.as_ref()) {
+            keys.extend(loaded.writable.iter().map(String::as_str));
+            keys.extend(loaded.readonly.iter().map(String::as_str));
+        }
+
+        keys
+    }
+
+    fn cpi_events(&self, program_id: &Pubkey) -> Result<Vec<ProgramEvent>> {
+        let Some(inner_instructions) = self
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.inner_instructions.as_ref())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let keys = self.account_keys();
+        let program_id = program_id.to_string();
+
+        let mut inner_instructions: Vec<&InnerInstructions> = inner_instructions.iter().collect();
+        inner_instructions.sort_by_key(|inner| inner.index);
+
+        inner_instructions
+            .into_iter()
+            .flat_map(|inner| inner.instructions.iter())
+            .filter(|ix| keys.get(ix.program_id_index) == Some(&program_id.as_str()))
+            .filter_map(|ix| bs58::decode(&ix.data).into_vec().ok())
+            .filter_map(|data| {
+                data.strip_prefix(&EVENT_IX_TAG_LE)
+                    .map(ProgramEvent::decode)
+            })
+            .filter_map(Result::transpose)
+            .collect()
+    }
+
+    fn log_events(&self, program_id: &Pubkey) -> Result<Vec<ProgramEvent>> {
+        let Some(logs) = self
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.log_messages.as_ref())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let program_id = program_id.to_string();
+        let mut stack: Vec<&str> = Vec::new();
+        let mut events = Vec::new();
+
+        for line in logs {
+            let Some(rest) = line.strip_prefix("Program ") else {
+                continue;
+            };
+
+            if let Some(data) = rest.strip_prefix("data: ") {
+                if stack.last() != Some(&program_id.as_str()) {
+                    continue;
+                }
+                let Ok(bytes) = STANDARD.decode(data) else {
+                    continue;
+                };
+                if let Some(event) = ProgramEvent::decode(&bytes)? {
+                    events.push(event);
+                }
+                continue;
+            }
+
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => stack.push(id),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+/// Parse a JSON dump into transactions.
+///
+/// Accepts a single transaction, an array of them, or raw `getTransaction`
+/// JSON-RPC responses (either shape, wrapped in `result`).
+pub fn parse_dump(contents: &str) -> Result<Vec<EncodedTransaction>> {
+    let value: serde_json::Value = serde_json::from_str(contents)?;
+
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        item => vec![item],
+    };
+
+    items
+        .into_iter()
+        .map(|item| match item {
+            serde_json::Value::Object(mut object) if object.contains_key("jsonrpc") => {
+                object.remove("result").ok_or_else(|| anyhow!("JSON-RPC response has no result"))
+            }
+            item => Ok(item),
+        })
+        .filter(|item| !matches!(item, Ok(serde_json::Value::Null)))
+        .map(|item| Ok(serde_json::from_value(item?)?))
+        .collect()
+}
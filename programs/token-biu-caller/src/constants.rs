@@ -0,0 +1 @@
+pub const BUYER: &[u8] = b"buyer";
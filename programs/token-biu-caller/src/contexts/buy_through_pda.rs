@@ -0,0 +1,144 @@
+use anchor_lang::{prelude::*, system_program};
+use token_biu::{cpi, cpi_helpers, program::TokenBiu};
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct BuyThroughPda<'info> {
+
+    /// Funds the purchase and pays the rent of the buyer's accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// This program's buyer, which pays for and owns the tokens bought
+    #[account(
+        mut,
+        seeds = [BUYER],
+        bump,
+    )]
+    pub buyer: SystemAccount<'info>,
+
+    /// CHECK: the sale's SOL recipient, checked by token_biu
+    #[account(mut)]
+    pub sale_authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(
+        mut,
+        address = cpi_helpers::program_sale_authority(&sale_config.key()),
+    )]
+    pub program_sale_authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(mut)]
+    pub sale_config: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(mut)]
+    pub program_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: the buyer's associated token account, created by token_biu
+    #[account(mut)]
+    pub buyer_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: the buyer's purchase record, created by token_biu
+    #[account(
+        mut,
+        address = cpi_helpers::wallet_purchase(&buyer.key()),
+    )]
+    pub wallet_purchase: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(
+        mut,
+        address = cpi_helpers::monthly_limits(&sale_config.key()),
+    )]
+    pub monthly_limits: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(
+        mut,
+        address = cpi_helpers::sale_stats(&sale_config.key()),
+    )]
+    pub sale_stats: UncheckedAccount<'info>,
+
+    /// CHECK: the Pyth SOL/USD price update, checked by token_biu
+    pub price_update: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: SPL Token or Token-2022, checked by token_biu
+    pub token_program: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    pub associated_token_program: UncheckedAccount<'info>,
+
+    /// CHECK: the instructions sysvar, checked by token_biu
+    pub instructions: UncheckedAccount<'info>,
+
+    /// CHECK: checked by token_biu
+    #[account(address = cpi_helpers::event_authority())]
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub token_biu_program: Program<'info, TokenBiu>,
+}
+
+impl<'info> BuyThroughPda<'info> {
+    /// Move `sol_amount` lamports from the payer to the buyer PDA, then have
+    /// the PDA spend them on tokens, signing for it
+    pub fn buy(
+        &self,
+        sol_amount: u64,
+        bumps: &BuyThroughPdaBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+
+        system_program::transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.buyer.to_account_info(),
+                },
+            ),
+            sol_amount,
+        )?;
+
+        let buyer_seeds: &[&[u8]] = &[BUYER, &[bumps.buyer]];
+
+        cpi_helpers::buy_tokens(
+            self.token_biu_program.to_account_info(),
+            cpi::accounts::BuyTokens {
+                buyer: self.buyer.to_account_info(),
+                payer: self.payer.to_account_info(),
+                sale_authority: self.sale_authority.to_account_info(),
+                program_sale_authority: self.program_sale_authority.to_account_info(),
+                beneficiary: self.buyer.to_account_info(),
+                sale_config: self.sale_config.to_account_info(),
+                authority: self.authority.to_account_info(),
+                mint: self.mint.to_account_info(),
+                program_token_account: self.program_token_account.to_account_info(),
+                buyer_token_account: self.buyer_token_account.to_account_info(),
+                wallet_purchase: self.wallet_purchase.to_account_info(),
+                monthly_limits: self.monthly_limits.to_account_info(),
+                sale_stats: self.sale_stats.to_account_info(),
+                price_update: self.price_update.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                associated_token_program: self.associated_token_program.to_account_info(),
+                instructions: self.instructions.to_account_info(),
+                event_authority: self.event_authority.to_account_info(),
+                program: self.token_biu_program.to_account_info(),
+            },
+            sol_amount,
+            &[buyer_seeds],
+            remaining_accounts.to_vec(),
+        )
+    }
+}
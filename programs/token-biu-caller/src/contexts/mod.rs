@@ -0,0 +1,3 @@
+mod buy_through_pda;
+
+pub use buy_through_pda::*;
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+pub mod constants;
+pub mod contexts;
+
+use contexts::*;
+
+declare_id!("FXn1jLjjmuArSuBH4o6udbMWBMd9G6VRMuCVPhw9SEnm");
+
+/// Example of a partner program buying from a `token_biu` sale through CPI,
+/// for a PDA it controls rather than a wallet
+#[program]
+pub mod token_biu_caller {
+    use super::*;
+
+    pub fn buy_through_pda<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyThroughPda<'info>>,
+        sol_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.buy(sol_amount, &ctx.bumps, ctx.remaining_accounts)
+    }
+}
@@ -0,0 +1,8 @@
+pub const EXTRA_ACCOUNT_METAS: &[u8] = b"extra-account-metas";
+
+// Accounts Token-2022 passes to `Execute` before the extra ones
+pub const SOURCE_TOKEN_INDEX: u8 = 0;
+pub const TOKEN_BIU_PROGRAM_INDEX: u8 = 5;
+
+pub const TOKEN_ACCOUNT_OWNER_OFFSET: u8 = 32; // Owner follows the mint in a token account
+pub const PUBKEY_LENGTH: u8 = 32;
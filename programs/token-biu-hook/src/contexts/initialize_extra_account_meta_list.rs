@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use token_biu::constants::WALLET_PURCHASE;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Written below as an `ExtraAccountMetaList`
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(extra_account_metas()?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS, mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeExtraAccountMetaList<'info> {
+    pub fn initialize(&mut self) -> Result<()> {
+
+        let mut data = self.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+        Ok(())
+    }
+}
+
+/// Extra accounts of every transfer: the `token_biu` program and the
+/// `WalletPurchase` of the source account's owner
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey(&token_biu::ID, false, false)?,
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            TOKEN_BIU_PROGRAM_INDEX,
+            &[
+                Seed::Literal {
+                    bytes: WALLET_PURCHASE.to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: SOURCE_TOKEN_INDEX,
+                    data_index: TOKEN_ACCOUNT_OWNER_OFFSET,
+                    length: PUBKEY_LENGTH,
+                },
+            ],
+            false,
+            false,
+        )?,
+    ])
+}
@@ -0,0 +1,5 @@
+mod initialize_extra_account_meta_list;
+mod transfer_hook;
+
+pub use initialize_extra_account_meta_list::*;
+pub use transfer_hook::*;
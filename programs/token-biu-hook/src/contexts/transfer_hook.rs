@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use token_biu::{constants::WALLET_PURCHASE, program::TokenBiu, states::WalletPurchase};
+use crate::{constants::*, error::ErrorCode};
+
+/// Accounts of the transfer-hook interface's `Execute`, in the order
+/// Token-2022 passes them
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Source owner or delegate, already checked by the token program
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Only its address matters here
+    #[account(
+        seeds = [EXTRA_ACCOUNT_METAS, mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub token_biu_program: Program<'info, TokenBiu>,
+
+    /// CHECK: Doesn't exist for wallets that never bought, e.g. the sale vault
+    #[account(
+        seeds = [WALLET_PURCHASE, source_token.owner.as_ref()],
+        bump,
+        seeds::program = token_biu_program.key(),
+    )]
+    pub wallet_purchase: UncheckedAccount<'info>,
+}
+
+impl<'info> TransferHook<'info> {
+    /// Reject transfers that leave the source owner holding less than their
+    /// still-locked purchases
+    pub fn check_lockup(&self, _amount: u64) -> Result<()> {
+
+        if self.wallet_purchase.owner != &token_biu::ID || self.wallet_purchase.data_is_empty() {
+            return Ok(());
+        }
+
+        let data = self.wallet_purchase.try_borrow_data()?;
+        let wallet_purchase = WalletPurchase::read(&data)?;
+        let locked = wallet_purchase.locked_at(Clock::get()?.unix_timestamp);
+
+        // Token-2022 runs the hook after moving the tokens, so this is the
+        // balance left
+        require!(self.source_token.amount >= locked, ErrorCode::TokensLocked);
+
+        Ok(())
+    }
+}
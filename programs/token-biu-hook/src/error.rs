@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+
+    #[msg("Purchased tokens are still locked")]
+    TokensLocked,
+}
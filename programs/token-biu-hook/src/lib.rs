@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+
+pub mod constants;
+pub mod contexts;
+pub mod error;
+
+use contexts::*;
+
+declare_id!("AYbLa8muRveqEfjVe6VorFHUXdcpcNPDzk3faJwLLcXT");
+
+#[program]
+pub mod token_biu_hook {
+    use super::*;
+
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        ctx.accounts.initialize()
+    }
+
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        ctx.accounts.check_lockup(amount)
+    }
+
+    /// Token-2022 calls the hook with the transfer-hook interface's `Execute`
+    /// discriminator rather than Anchor's, so route it to `transfer_hook`
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::transfer_hook(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
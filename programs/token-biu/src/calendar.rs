@@ -0,0 +1,160 @@
+//! UTC calendar arithmetic for schedules that unlock on the 1st of each
+//! month. Proleptic Gregorian calendar, so leap years follow the usual
+//! 4/100/400 rule; no floating point or syscalls, so the same functions run
+//! on-chain and off-chain.
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const DAYS_PER_ERA: i64 = 146_097; // 400 years
+const UNIX_EPOCH_DAYS: i64 = 719_468; // 1970-03-01 counted from 0000-03-01
+const MAX_YEAR: i64 = 9999;
+
+/// Days since 1970-01-01 of a UTC date
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * DAYS_PER_ERA + day_of_era - UNIX_EPOCH_DAYS
+}
+
+/// UTC date (year, month, day) of a day counted from 1970-01-01
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+
+    let days = days + UNIX_EPOCH_DAYS;
+    let era = days.div_euclid(DAYS_PER_ERA);
+    let day_of_era = days - era * DAYS_PER_ERA;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u32;
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Unix timestamp of 00:00 UTC on the 1st of `month` in `year`, for years
+/// 0 through 9999
+pub fn month_start_timestamp(year: i64, month: u32) -> Option<i64> {
+
+    if !(0..=MAX_YEAR).contains(&year) || !(1..=12).contains(&month) {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, 1) * SECONDS_PER_DAY)
+}
+
+/// Whether `timestamp` is exactly 00:00 UTC on the 1st of a month
+pub fn is_month_start(timestamp: i64) -> bool {
+
+    let (_, _, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+
+    day == 1 && timestamp.rem_euclid(SECONDS_PER_DAY) == 0
+}
+
+/// 00:00 UTC on the 1st of the month `months` months after the one
+/// containing `timestamp`
+pub fn add_months(timestamp: i64, months: i64) -> Option<i64> {
+
+    let (year, month, _) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    let month_index = year
+        .checked_mul(12)?
+        .checked_add(month as i64 - 1)?
+        .checked_add(months)?;
+
+    month_start_timestamp(month_index.div_euclid(12), month_index.rem_euclid(12) as u32 + 1)
+}
+
+/// `count` consecutive month starts, the first being the month containing
+/// `start_time`
+pub fn month_starts(start_time: i64, count: usize) -> Option<Vec<i64>> {
+    (0..count as i64).map(|index| add_months(start_time, index)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_round_trip_through_civil_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(days_from_civil(2024, 1, 1), 19_723);
+
+        for days in (days_from_civil(0, 1, 1)..=days_from_civil(MAX_YEAR, 12, 31)).step_by(97) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn leap_days_follow_the_gregorian_rule() {
+        // 2024 is divisible by 4, 2100 by 100 only and 2000 by 400
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 28) + 1), (2024, 2, 29));
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29) + 1), (2024, 3, 1));
+        assert_eq!(civil_from_days(days_from_civil(2100, 2, 28) + 1), (2100, 3, 1));
+        assert_eq!(civil_from_days(days_from_civil(2000, 2, 28) + 1), (2000, 2, 29));
+
+        let february = |year| {
+            (month_start_timestamp(year, 3).unwrap() - month_start_timestamp(year, 2).unwrap())
+                / SECONDS_PER_DAY
+        };
+        assert_eq!(february(2023), 28);
+        assert_eq!(february(2024), 29);
+        assert_eq!(february(2100), 28);
+        assert_eq!(february(2000), 29);
+    }
+
+    #[test]
+    fn month_start_timestamp_rejects_out_of_range_dates() {
+        assert_eq!(month_start_timestamp(2024, 1), Some(1_704_067_200));
+        assert_eq!(month_start_timestamp(2024, 0), None);
+        assert_eq!(month_start_timestamp(2024, 13), None);
+        assert_eq!(month_start_timestamp(-1, 12), None);
+        assert_eq!(month_start_timestamp(MAX_YEAR + 1, 1), None);
+    }
+
+    #[test]
+    fn add_months_crosses_year_boundaries() {
+        let january_2024 = month_start_timestamp(2024, 1).unwrap();
+        let mid_november_2023 = month_start_timestamp(2023, 11).unwrap() + 14 * SECONDS_PER_DAY + 5;
+
+        assert_eq!(add_months(mid_november_2023, 0), month_start_timestamp(2023, 11));
+        assert_eq!(add_months(mid_november_2023, 2), Some(january_2024));
+        assert_eq!(add_months(january_2024, -1), month_start_timestamp(2023, 12));
+        assert_eq!(add_months(january_2024, 25), month_start_timestamp(2026, 2));
+        assert_eq!(add_months(january_2024, -24 * 12 - 1), month_start_timestamp(1999, 12));
+        assert_eq!(add_months(month_start_timestamp(MAX_YEAR, 12).unwrap(), 1), None);
+        assert_eq!(add_months(january_2024, i64::MAX), None);
+    }
+
+    #[test]
+    fn month_starts_are_consecutive() {
+        let starts = month_starts(month_start_timestamp(2023, 11).unwrap() + 1, 4).unwrap();
+
+        assert_eq!(
+            starts,
+            [(2023, 11), (2023, 12), (2024, 1), (2024, 2)]
+                .map(|(year, month)| month_start_timestamp(year, month).unwrap())
+        );
+        assert!(starts.iter().all(|&start| is_month_start(start)));
+    }
+
+    #[test]
+    fn is_month_start_needs_midnight_on_the_first() {
+        let march_2024 = month_start_timestamp(2024, 3).unwrap();
+
+        assert!(is_month_start(march_2024));
+        assert!(is_month_start(0));
+        assert!(!is_month_start(march_2024 + 1));
+        assert!(!is_month_start(march_2024 - 1));
+        assert!(!is_month_start(march_2024 + SECONDS_PER_DAY));
+        assert!(is_month_start(month_start_timestamp(1969, 12).unwrap()));
+    }
+}
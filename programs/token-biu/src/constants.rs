@@ -5,18 +5,25 @@ pub const SOL_USD_FEED_ID: &str =
     "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
 
 pub const SALE_AUTHORITY: &[u8] = b"SALE_AUTHORITY";
+pub const EVENT_AUTHORITY: &[u8] = b"__event_authority";
+pub const WALLET_PURCHASE: &[u8] = b"wallet_purchase";
+pub const MONTHLY_LIMITS: &[u8] = b"monthly_limits_a";
+pub const SALE_STATS: &[u8] = b"sale_stats";
 
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
 pub const MAX_AGE: u64 = 100;
 pub const PRICE_FEED_DECIMAL_ADJUSTMENT: u128 = 10;
 pub const SECONDS_IN_A_DAY: i64 = 24 * 60 * 60;
 
-// Define an enum for the periods
-#[derive(Debug, Clone, Copy)]
+// Define an enum for the periods; the schedule account stores the one it uses
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Period {
     Hourly,
     Daily,
+    Weekly,
     Monthly,
+    Custom(i64), // Any length, in seconds
+    CalendarMonthly, // 00:00 UTC on the 1st of each month
 }
 
 impl Period {
@@ -25,7 +32,10 @@ impl Period {
         match self {
             Period::Hourly => 3600,
             Period::Daily => 86400,
+            Period::Weekly => 604800,
             Period::Monthly => 2629743,
+            Period::Custom(seconds) => *seconds,
+            Period::CalendarMonthly => 2629743, // Average month, actual months vary
         }
     }
 
@@ -34,25 +44,49 @@ impl Period {
         match self {
             Period::Hourly => 12,
             Period::Daily => 12,
+            Period::Weekly => 12,
             Period::Monthly => 12,
+            Period::Custom(_) => 12,
+            Period::CalendarMonthly => 12,
+        }
+    }
+
+    // Method to check the period has a positive length
+    pub const fn is_valid(&self) -> bool {
+        self.length() > 0
+    }
+
+    // Method to get the start of period `index` of a schedule starting at `start_time`
+    pub fn start(&self, start_time: i64, index: i64) -> Option<i64> {
+        match self {
+            Period::CalendarMonthly => crate::calendar::add_months(start_time, index),
+            _ => index
+                .checked_mul(self.length())
+                .and_then(|offset| start_time.checked_add(offset)),
         }
     }
-}
 
-// Use the enum to define the constants
+    // Method to check `start_time` can start a schedule of this period
+    pub fn is_valid_start(&self, start_time: i64) -> bool {
+        match self {
+            Period::CalendarMonthly => crate::calendar::is_month_start(start_time),
+            _ => true,
+        }
+    }
+}
 
 /*
 
 Period::Monthly => For monthly periods ~30.44 days
+Period::Weekly => For weekly periods - 7 days
 Period::Daily => For daily periods - 24 hours
 Period::Hourly => For hourly periods - 1 hour
+Period::Custom(seconds) => For periods of any length
+Period::CalendarMonthly => For periods starting on the 1st of each month, 00:00 UTC
 
 */
 
-pub const PERIOD: Period = Period::Monthly;
-
-pub const PERIOD_LENGTH: i64 = PERIOD.length();
-pub const PERIOD_COUNT: i64 = PERIOD.count();
+pub const DEFAULT_PERIOD: Period = Period::Monthly; // Period for new schedules unless another is chosen
 
 pub const DEFAULT: u64 = 0;
 pub const MONTHS_IN_A_YEAR: u8 = 12;
@@ -61,9 +95,15 @@ pub const MONTHS_IN_A_YEAR: u8 = 12;
 pub const SOL_DECIMALS: f64 = 9.0; // SOL has 9 decimal places
 
 // Space constants
-pub const MONTHLY_LIMITS_SIZE: usize = 8 + (8 * 14) + (8 * 14) + 8 + 8 + 8 + 1 + 1 ; // Size of MonthlyLimits account
-pub const WALLET_PURCHASE_SIZE: usize = 8 + 32 + 8 + 8 + 1; // Size of WalletPurchase account
-pub const SALE_CONFIG_SIZE: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1; // Size of SaleConfig account
+pub const MONTHLY_LIMITS_SIZE: usize = 8 + 4 + 4 + 4 + 8 + 8 + 8 + 8 + 8 + 2 + 1 + 1 + 1 + 8 + 8 + 8 + PERIOD_SIZE + 4 + 4; // Size of MonthlyLimits account without periods
+pub const PERIOD_SIZE: usize = 1 + 8; // Largest `Period`, a custom length
+pub const MONTHLY_LIMITS_PERIOD_SIZE: usize = 8 + 8 + 8 + 8 + 8; // Timestamp, sale and treasury limit, tokens sold and SOL raised of one period
+pub const WALLET_PURCHASE_SIZE: usize = 8 + 32 + 8 + 8 + 1 + 32 + 8 + 4; // Size of WalletPurchase account without locks
+pub const LOCK_SIZE: usize = 8 + 8; // Amount and unlock timestamp of one purchase lock
+pub const MAX_LOCKS: usize = 32; // Purchases a wallet can have locked at once
+pub const LEGACY_WALLET_PURCHASE_SIZE: usize = 8 + 32 + 8 + 8 + 1; // Size of the first release's WalletPurchase account
+pub const SALE_CONFIG_SIZE: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1 + 8 + 1 + 8 + 8 + 1 + 8 + 8 + 1 + 8 + 8; // Size of SaleConfig account
+pub const SALE_STATS_SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1; // Size of SaleStats account
 
 // Vesting schedule constants
 pub const YEAR1_START: i64 = 1735689600; // March 1, 2025
@@ -75,4 +115,6 @@ pub const MARCH: i64 = 3;
 pub const SEPTEMBER: u8 = 9;
 pub const FIRST_HALF: usize = 12;
 pub const SECOND_HALF: usize = 13;
+pub const GENERATED_PERIODS: usize = SECOND_HALF + 1; // Periods in a generated schedule
+pub const MAX_PERIODS: usize = 256; // Keeps a full schedule within one realloc
 
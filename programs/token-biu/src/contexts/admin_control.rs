@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use crate::{events::*, states::*};
+use crate::{error::ErrorCode, events::*, states::*};
 
+#[event_cpi]
 #[derive(Accounts)]
 
     pub struct AdminControl<'info> {
@@ -12,39 +13,105 @@ use crate::{events::*, states::*};
 }
 
 impl<'info> AdminControl<'info> {
-    pub fn change_recipient(&mut self, new_recipient: Pubkey) -> Result<()> {
+    pub fn change_recipient(&mut self, new_recipient: Pubkey, bumps: &AdminControlBumps) -> Result<()> {
 
         let old_recipient = self.sale_config.recipient;
         self.sale_config.recipient = new_recipient;
 
-        emit!(RecipientChanged {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &RecipientChanged {
             old_recipient,
             new_recipient,
-        });
+        })?;
 
         Ok(())
     }
 
-    pub fn change_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+    pub fn change_authority(&mut self, new_authority: Pubkey, bumps: &AdminControlBumps) -> Result<()> {
 
         let old_authority = self.sale_config.authority;
         self.sale_config.authority = new_authority;
 
-        emit!(TokenAuthorityChanged {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &TokenAuthorityChanged {
             old_authority,
             new_authority,
-        });
+        })?;
 
         Ok(())
     }
 
-    pub fn set_limit(&mut self, new_limit: u64) -> Result<()> {
+    pub fn set_limit(&mut self, new_limit: u64, bumps: &AdminControlBumps) -> Result<()> {
 
         self.sale_config.wallet_purchase_limit = new_limit;
 
-        emit!(WalletLimitSet {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &WalletLimitSet {
             new_limit
-        });
+        })?;
+
+        Ok(())
+    }
+
+    /// Seconds purchased tokens stay locked in the buyer's wallet; 0 turns
+    /// lockups off for new purchases
+    pub fn set_lockup_duration(&mut self, lockup_duration: i64, bumps: &AdminControlBumps) -> Result<()> {
+
+        require!(lockup_duration >= 0, ErrorCode::InvalidLockupDuration);
+
+        self.sale_config.lockup_duration = lockup_duration;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &LockupDurationSet {
+            lockup_duration
+        })?;
+
+        Ok(())
+    }
+
+    /// Purchases open at `start_time` and close at `end_time`, 0 for no end;
+    /// only before the sale has ended
+    pub fn set_sale_window(&mut self, start_time: i64, end_time: i64, bumps: &AdminControlBumps) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        require!(
+            matches!(self.sale_config.current_state(current_timestamp), SaleState::Draft | SaleState::Active),
+            ErrorCode::InvalidSaleState
+        );
+        require!(end_time == 0 || end_time > start_time, ErrorCode::InvalidSaleWindow);
+
+        self.sale_config.start_time = start_time;
+        self.sale_config.end_time = end_time;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleWindowSet {
+            start_time,
+            end_time,
+        })?;
+
+        Ok(())
+    }
+
+    /// Choose what `finalize_sale` does with the tokens left in the vault
+    pub fn set_unsold_policy(&mut self, unsold_policy: UnsoldPolicy, bumps: &AdminControlBumps) -> Result<()> {
+
+        require!(self.sale_config.state != SaleState::Finalized, ErrorCode::InvalidSaleState);
+
+        self.sale_config.unsold_policy = unsold_policy;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &UnsoldPolicySet {
+            unsold_policy
+        })?;
+
+        Ok(())
+    }
+
+    /// Move the sale along its lifecycle, emitting the transition
+    pub fn transition(&mut self, new_state: SaleState, bumps: &AdminControlBumps) -> Result<()> {
+
+        let old_state = self.sale_config.transition(new_state)?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleStateChanged {
+            old_state,
+            new_state,
+            timestamp: Clock::get()?.unix_timestamp,
+        })?;
 
         Ok(())
     }
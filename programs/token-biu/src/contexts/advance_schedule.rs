@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::{events::*, states::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AdvanceSchedule<'info> {
+
+    pub sale_config: Account<'info, SaleConfig>,
+
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub monthly_limits: Account<'info, MonthlyLimits>,
+}
+
+impl<'info> AdvanceSchedule<'info> {
+    /// Unlock every period reached so far without waiting for a purchase or
+    /// withdrawal; anyone can call it
+    pub fn advance(&mut self, bumps: &AdvanceScheduleBumps) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        let advance = self.monthly_limits.unlock_periods(current_timestamp)?;
+
+        require!(
+            self.monthly_limits.periods_unlocked > advance.first_period,
+            ErrorCode::NothingToAdvance
+        );
+
+        emit_advance(&self.event_authority, bumps.event_authority, &self.monthly_limits, &advance)?;
+
+        Ok(())
+    }
+}
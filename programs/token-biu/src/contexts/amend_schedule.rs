@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{states::*, events::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(from_index: u16, limits: Vec<u64>)]
+pub struct AmendSchedule<'info> {
+
+    #[account(
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+        realloc = MonthlyLimits::space(from_index as usize + limits.len()),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AmendSchedule<'info> {
+    /// Replace the periods from `from_index` on with new ones, shifting,
+    /// changing, adding or dropping future periods without touching the
+    /// accounting of those already reached
+    pub fn amend(
+        &mut self,
+        from_index: u16,
+        limits: Vec<u64>,
+        treasury_limits: Vec<u64>,
+        timestamps: Vec<i64>,
+        bumps: &AmendScheduleBumps,
+    ) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        self.monthly_limits.amend_schedule(
+            from_index as usize,
+            &limits,
+            &treasury_limits,
+            &timestamps,
+            current_timestamp,
+        )?;
+        self.monthly_limits.require_funded(
+            self.sale_config.token_capacity(self.sale_config.deposited_balance(), self.mint.supply),
+        )?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &ScheduleAmended {
+            from_index: from_index as u64,
+            limits,
+            treasury_limits,
+            timestamps,
+            total_periods: self.monthly_limits.timestamps.len() as u64,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{states::*, events::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(limits: Vec<u64>)]
+pub struct AppendMonthlyLimits<'info> {
+
+    #[account(
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+        realloc = MonthlyLimits::space(monthly_limits.limits.len() + limits.len()),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AppendMonthlyLimits<'info> {
+    pub fn append_limits(
+        &mut self,
+        limits: Vec<u64>,
+        treasury_limits: Vec<u64>,
+        timestamps: Vec<i64>,
+        bumps: &AppendMonthlyLimitsBumps,
+    ) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let from_index = self.monthly_limits.timestamps.len();
+
+        MonthlyLimits::validate_length(&limits, &treasury_limits, &timestamps)?;
+        self.monthly_limits.amend_schedule(
+            from_index,
+            &limits,
+            &treasury_limits,
+            &timestamps,
+            current_timestamp,
+        )?;
+        self.monthly_limits.require_funded(
+            self.sale_config.token_capacity(self.sale_config.deposited_balance(), self.mint.supply),
+        )?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &MonthlyLimitsAppended {
+            limits,
+            treasury_limits,
+            timestamps,
+            total_periods: self.monthly_limits.timestamps.len() as u64,
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,30 +1,51 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{self, Mint, TokenAccount, TokenInterface},
 };
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
-use crate::{states::*, events::*, constants::*, error::ErrorCode};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::{states::*, events::*, constants::*, error::ErrorCode, fees, vault::{self, Vault}, pricing, rent};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(sol_amount: u64)]
 pub struct BuyTokens<'info> {
 
+    /// Pays for the tokens; may be a PDA of a calling program
     #[account(mut)]
     pub buyer: Signer<'info>,
 
+    /// Pays the rent of the beneficiary's token account and purchase record;
+    /// the buyer again when it is a wallet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(mut)]
     pub sale_authority: SystemAccount<'info>,
 
     #[account(
         mut,
-        seeds = [SALE_AUTHORITY],
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
         bump = sale_config.bump,
     )]
     pub program_sale_authority: SystemAccount<'info>,
 
+    /// Receives the tokens and is charged the purchase limits and lockup, so
+    /// it signs too; the buyer again when buying for itself
+    #[account(
+        constraint = beneficiary.key() != program_sale_authority.key() @ ErrorCode::InvalidBeneficiary
+    )]
+    pub beneficiary: Signer<'info>,
+
 
     #[account(
+        mut,
         constraint = sale_config.authority == authority.key() @ ErrorCode::Unauthorized,
         constraint = sale_config.sale_authority == program_sale_authority.key() @ ErrorCode::WrongProgramAuthority,
         constraint = sale_config.recipient == sale_authority.key() @ ErrorCode::WrongRecipientAddress,
@@ -35,68 +56,120 @@ pub struct BuyTokens<'info> {
     /// CHECK: We only need the public key for verification
     pub authority: UncheckedAccount<'info>,
 
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = program_sale_authority
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
     )]
-    pub program_token_account: Account<'info, TokenAccount>,
+    pub program_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
-        payer = buyer,
+        payer = payer,
         associated_token::mint = mint,
-        associated_token::authority = buyer,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub buyer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
-        payer = buyer,
-        space = WALLET_PURCHASE_SIZE,
-        seeds = [b"wallet_purchase", buyer.key().as_ref()],
+        payer = payer,
+        space = WalletPurchase::space(0),
+        seeds = [WALLET_PURCHASE, beneficiary.key().as_ref()],
         bump,
     )]
     pub wallet_purchase: Box<Account<'info, WalletPurchase>>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
     pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
 
+    #[account(
+        mut,
+        seeds = [SALE_STATS, sale_config.key().as_ref()],
+        bump = sale_stats.bump,
+    )]
+    pub sale_stats: Box<Account<'info, SaleStats>>,
+
     pub price_update: Box<Account<'info, PriceUpdateV2>>,
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: the instructions sysvar, read to find the calling program
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
 }
 
 impl<'info> BuyTokens<'info> {
-    pub fn buy(&mut self, sol_amount: u64) -> Result<()> {
+    pub fn buy(
+        &mut self,
+        sol_amount: u64,
+        bumps: &BuyTokensBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
 
         let current_timestamp = Clock::get()?.unix_timestamp;
 
         self.check_sale_paused()?;
-        self.initialize_wallet_purchase_if_needed()?;
+        self.sale_config.require_open(current_timestamp)?;
+        let new_buyer = self.initialize_wallet_purchase_if_needed()?;
 
         let sol_price_usd = self.get_sol_price_usd()?;
         let token_amount = self.calculate_token_amount(sol_amount, sol_price_usd)?;
+        let transfer_amount = self.calculate_transfer_amount(token_amount)?;
 
         self.check_wallet_purchase_limits(token_amount, current_timestamp)?;
-        self.check_monthly_limits(token_amount, current_timestamp)?;
+        let advance = self.draw_sale_allowance(token_amount, current_timestamp)?;
 
         self.transfer_sol(sol_amount)?;
-        self.transfer_tokens(token_amount)?;
+        if self.sale_config.mint_on_purchase {
+            self.mint_tokens(token_amount, bumps)?;
+        } else {
+            self.transfer_tokens(transfer_amount, remaining_accounts)?;
+            self.sale_config.release(transfer_amount)?;
+        }
+        emit_advance(&self.event_authority, bumps.event_authority, &self.monthly_limits, &advance)?;
+        self.burn_unsold_allocation(bumps)?;
 
-        self.update_state(token_amount, current_timestamp)?;
+        self.update_state(sol_amount, sol_price_usd, token_amount, current_timestamp, new_buyer)?;
+        self.lock_purchase(token_amount, current_timestamp, bumps)?;
 
-        self.emit_purchase_event(sol_amount, token_amount, sol_price_usd)?;
+        self.emit_purchase_events(sol_amount, token_amount, transfer_amount - token_amount, sol_price_usd, bumps)?;
 
         Ok(())
     }
 
+    /// Program whose top-level instruction reached this purchase through CPI,
+    /// `None` when the purchase was sent directly. Only the outermost program
+    /// is known: for A → B → token_biu this is A, not B
+    fn caller_program(&self) -> Result<Option<Pubkey>> {
+
+        if get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT {
+            return Ok(None);
+        }
+
+        let instructions = self.instructions.to_account_info();
+        let index = load_current_index_checked(&instructions)?;
+        let program_id = load_instruction_at_checked(index as usize, &instructions)?.program_id;
+
+        Ok(Some(program_id))
+    }
+
     /// Check if the sale is paused
     fn check_sale_paused(&self) -> Result<()> {
 
@@ -105,51 +178,57 @@ impl<'info> BuyTokens<'info> {
         Ok(())
     }
 
-    /// Initialize wallet purchase if needed
-    fn initialize_wallet_purchase_if_needed(&mut self) -> Result<()> {
+    /// Initialize wallet purchase if needed; returns whether this is the
+    /// beneficiary's first purchase in this sale
+    fn initialize_wallet_purchase_if_needed(&mut self) -> Result<bool> {
 
-        if self.wallet_purchase.wallet == Pubkey::default() {
-            self.wallet_purchase.wallet = self.buyer.key();
-            self.wallet_purchase.total_purchased = DEFAULT;
-            self.wallet_purchase.last_purchased_timestamp = DEFAULT as i64;
+        if self.wallet_purchase.wallet != Pubkey::default() {
+            return Ok(self.wallet_purchase.sale_config != self.sale_config.key());
         }
 
-        Ok(())
+        self.wallet_purchase.wallet = self.beneficiary.key();
+        self.wallet_purchase.total_purchased = DEFAULT;
+        self.wallet_purchase.last_purchased_timestamp = DEFAULT as i64;
+        self.wallet_purchase.lifetime_purchased = DEFAULT;
+
+        Ok(true)
     }
 
     /// Get SOL/USD price from Pyth
     fn get_sol_price_usd(&self) -> Result<f64> {
 
-        let feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
-        let price_data = self.price_update.get_price_no_older_than(
-            &Clock::get()?,
-            MAX_AGE,
-            &feed_id,
-        )?;
-        let sol_price_usd = (price_data.price as f64) * 10f64.powi(price_data.exponent);
-
-        Ok(sol_price_usd)
+        pricing::sol_price_usd(&self.price_update)
     }
 
     /// Calculate token amount based on SOL amount and price
     fn calculate_token_amount(&self, sol_amount: u64, sol_price_usd: f64) -> Result<u64> {
 
-        let token_price_usd = self.sale_config.token_price_usd;
-        let sol_amount_usd = sol_amount as f64 / 10_f64.powf(SOL_DECIMALS) * sol_price_usd;
-        let decimals = self.sale_config.mint_decimals;
-        let token_amount = (sol_amount_usd / token_price_usd * 10_f64.powf(decimals as f64)) as u64;
+        let token_amount = self.sale_config.token_amount(sol_amount, sol_price_usd);
 
-        require!(self.program_token_account.amount >= token_amount, ErrorCode::InsufficientTokens);
+        require!(token_amount > 0, ErrorCode::PurchaseTooSmall);
 
         Ok(token_amount)
     }
 
+    /// Tokens leaving the vault so the buyer receives `token_amount` after
+    /// any Token-2022 transfer fee; minted purchases pay no fee
+    fn calculate_transfer_amount(&self, token_amount: u64) -> Result<u64> {
+
+        if self.sale_config.mint_on_purchase {
+            return Ok(token_amount);
+        }
+
+        let transfer_amount = fees::gross_amount(&self.mint.to_account_info(), token_amount)?;
+
+        require!(self.program_token_account.amount >= transfer_amount, ErrorCode::InsufficientTokens);
+
+        Ok(transfer_amount)
+    }
+
     /// Check wallet purchase limits
     fn check_wallet_purchase_limits(&mut self, token_amount: u64, current_timestamp: i64) -> Result<()> {
 
-        if current_timestamp - self.wallet_purchase.last_purchased_timestamp > SECONDS_IN_A_DAY {
-            self.wallet_purchase.total_purchased = DEFAULT;
-        }
+        self.wallet_purchase.total_purchased = self.wallet_purchase.window_purchased(current_timestamp);
 
         require!(
             self.wallet_purchase.total_purchased + token_amount <= self.sale_config.wallet_purchase_limit,
@@ -159,58 +238,23 @@ impl<'info> BuyTokens<'info> {
         Ok(())
     }
 
-    /// Check monthly limits
-    fn check_monthly_limits(&mut self, token_amount: u64, current_timestamp: i64) -> Result<()> {
+    /// Take the purchase out of the schedule's sale allowance
+    fn draw_sale_allowance(&mut self, token_amount: u64, current_timestamp: i64) -> Result<Advance> {
 
-       if !self.monthly_limits.is_vesting_enabled{
-            return Ok(());
+        if !self.monthly_limits.is_vesting_enabled {
+            return Ok(Advance {
+                first_period: self.monthly_limits.periods_unlocked,
+                expired: DEFAULT,
+            });
         }
 
-        let timestamps = self.monthly_limits.timestamps;
-        let limits = self.monthly_limits.limits;
-
-        if self.monthly_limits.last_checked_index  as usize >= timestamps.len() - 1{
-            self.monthly_limits.tokens_available = self.program_token_account.amount;
-            return Ok(());
-        } 
-
-        if current_timestamp <= self.monthly_limits.timestamps[self.monthly_limits.last_checked_index  as usize + 1]{
-
-            if current_timestamp < self.monthly_limits.timestamps[DEFAULT as usize]{
-                return Err(ErrorCode::SaleNotStarted.into());
-            }
-
-            if self.monthly_limits.tokens_available == 0 && self.monthly_limits.tokens_unlocked == 0{
-                self.monthly_limits.tokens_available = limits[self.monthly_limits.last_checked_index as usize];
-            }
-
-
-        require!(
-            token_amount <= self.monthly_limits.tokens_available,
-            ErrorCode::MonthlyLimitExceeded
-        );
-
-            Ok(())
-        }
-
-        else{
-            let mut i = self.monthly_limits.last_checked_index as usize + 1;
-            let mut temp_var  = self.monthly_limits.last_checked_index as usize;
-
-            while i < timestamps.len() && current_timestamp >= timestamps[i]{
-                temp_var = i;
-                self.monthly_limits.tokens_available += limits[i];
-                i += 1;
-            }
-            self.monthly_limits.last_checked_index = temp_var as u8;
-
-        require!(
-            token_amount <= self.monthly_limits.tokens_available,
-            ErrorCode::MonthlyLimitExceeded
-        );
-            Ok(())
-        }
-   }
+        self.monthly_limits.draw(
+            Allowance::Sale,
+            token_amount,
+            current_timestamp,
+            self.sale_config.token_capacity(self.program_token_account.amount, self.mint.supply),
+        )
+    }
 
     /// Transfer SOL from buyer to sale authority
     fn transfer_sol(&self, sol_amount: u64) -> Result<()> {
@@ -227,19 +271,30 @@ impl<'info> BuyTokens<'info> {
         Ok(())
     }
 
-    /// Transfer tokens from program to buyer
-    fn transfer_tokens(&self, token_amount: u64) -> Result<()> {
+    /// Transfer tokens from program to the beneficiary
+    fn transfer_tokens(&self, transfer_amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+
+        self.vault().send(self.buyer_token_account.to_account_info(), transfer_amount, remaining_accounts)
+    }
 
+    /// Mint the purchase straight to the beneficiary, within the max supply
+    fn mint_tokens(&mut self, token_amount: u64, bumps: &BuyTokensBumps) -> Result<()> {
+
+        let supply = self.mint.supply.checked_add(token_amount).ok_or(ErrorCode::MathOverflow)?;
+        require!(supply <= self.sale_config.max_supply, ErrorCode::MaxSupplyExceeded);
+
+        let sale_config = self.sale_config.key();
         let authority_seeds: &[&[u8]] = &[
             SALE_AUTHORITY,
+            sale_config.as_ref(),
             &[self.sale_config.bump],
         ];
 
-        anchor_spl::token::transfer(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: self.program_token_account.to_account_info(),
+                token_interface::MintTo {
+                    mint: self.mint.to_account_info(),
                     to: self.buyer_token_account.to_account_info(),
                     authority: self.program_sale_authority.to_account_info(),
                 },
@@ -248,28 +303,132 @@ impl<'info> BuyTokens<'info> {
             token_amount
         )?;
 
+        self.sale_config.tokens_minted = self
+            .sale_config
+            .tokens_minted
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &TokensMinted {
+            buyer: self.beneficiary.key(),
+            token_amount,
+            tokens_minted: self.sale_config.tokens_minted,
+            supply,
+        })?;
+
         Ok(())
     }
 
+    /// The vault purchases are paid out of
+    fn vault(&self) -> Vault<'info> {
+        Vault::new(
+            &self.token_program,
+            &self.mint,
+            &self.program_token_account,
+            &self.program_sale_authority,
+            &self.sale_config,
+        )
+    }
+
+    /// Burn the unsold allocation the carry-over policy sent to be burned
+    fn burn_unsold_allocation(&mut self, bumps: &BuyTokensBumps) -> Result<()> {
+
+        vault::burn_unsold_allocation(
+            &self.vault(),
+            self.program_token_account.amount,
+            &mut self.sale_config,
+            &mut self.monthly_limits,
+            &self.event_authority,
+            bumps.event_authority,
+        )
+    }
+
     /// Update state after purchase
-    fn update_state(&mut self, token_amount: u64, current_timestamp: i64) -> Result<()> {
+    fn update_state(
+        &mut self,
+        sol_amount: u64,
+        sol_price_usd: f64,
+        token_amount: u64,
+        current_timestamp: i64,
+        new_buyer: bool,
+    ) -> Result<()> {
 
         self.wallet_purchase.total_purchased += token_amount;
         self.wallet_purchase.last_purchased_timestamp = current_timestamp;
-        self.monthly_limits.tokens_unlocked += token_amount;
-        self.monthly_limits.tokens_available -= token_amount;
+        self.wallet_purchase.lifetime_purchased = self.wallet_purchase.lifetime_purchased
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.wallet_purchase.sale_config = self.sale_config.key();
+
+        self.monthly_limits.record_period_sale(token_amount, sol_amount, current_timestamp)?;
+        self.sale_stats.record_purchase(
+            sol_amount,
+            SaleConfig::usd_value(sol_amount, sol_price_usd),
+            token_amount,
+            new_buyer,
+        )?;
 
         Ok(())
     }
 
-    /// Emit purchase event
-    fn emit_purchase_event(&self, sol_amount: u64, token_amount: u64, sol_price_usd: f64) -> Result<()> {
-        emit!(TokensPurchased {
+    /// Lock the purchased tokens in the beneficiary's wallet for the sale's lockup
+    /// duration; the transfer hook enforces it on Token-2022 mints
+    fn lock_purchase(&mut self, token_amount: u64, current_timestamp: i64, bumps: &BuyTokensBumps) -> Result<()> {
+
+        let lockup_duration = self.sale_config.lockup_duration;
+        if lockup_duration == 0 {
+            return Ok(());
+        }
+
+        self.wallet_purchase.lock(token_amount, current_timestamp, lockup_duration)?;
+
+        let space = WalletPurchase::space(self.wallet_purchase.locks.len());
+        if space > self.wallet_purchase.to_account_info().data_len() {
+            rent::resize(
+                &self.wallet_purchase.to_account_info(),
+                &self.payer.to_account_info(),
+                &self.system_program.to_account_info(),
+                space,
+            )?;
+        }
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &TokensLocked {
+            buyer: self.beneficiary.key(),
+            token_amount,
+            locked_amount: self.wallet_purchase.locked_at(current_timestamp),
+            unlock_timestamp: current_timestamp + lockup_duration,
+        })?;
+
+        Ok(())
+    }
+
+    /// Emit purchase event, and the sale allowance left when vesting applies
+    fn emit_purchase_events(
+        &self,
+        sol_amount: u64,
+        token_amount: u64,
+        transfer_fee: u64,
+        sol_price_usd: f64,
+        bumps: &BuyTokensBumps,
+    ) -> Result<()> {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &TokensPurchased {
             buyer: self.buyer.key(),
+            payer: self.payer.key(),
+            caller_program: self.caller_program()?,
+            beneficiary: self.beneficiary.key(),
             sol_amount,
             token_amount,
             sol_price: sol_price_usd,
-        });
+            transfer_fee,
+        })?;
+
+        if self.monthly_limits.is_vesting_enabled {
+            emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleAllowanceUsed {
+                token_amount,
+                sale_available: self.monthly_limits.sale_available,
+                tokens_sold: self.monthly_limits.tokens_sold,
+            })?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{events::*, states::*, constants::*, error::ErrorCode, vault::Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseSale<'info> {
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint,
+        constraint = sale_config.state == SaleState::Finalized @ ErrorCode::SaleNotFinalized,
+        close = authority,
+    )]
+    pub sale_config: Account<'info, SaleConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SALE_STATS, sale_config.key().as_ref()],
+        bump = sale_stats.bump,
+        close = authority,
+    )]
+    pub sale_stats: Account<'info, SaleStats>,
+
+    /// Closed too when the sale had a schedule
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+        close = authority,
+    )]
+    pub monthly_limits: Option<Account<'info, MonthlyLimits>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CloseSale<'info> {
+    /// Close the finalized sale's accounts, returning their rent to the
+    /// authority. Tokens sent to the vault after `finalize_sale` emptied it
+    /// are burned, so they can't keep it open. The mint authority must be
+    /// returned first, as nothing could hand it back afterwards.
+    pub fn close(&mut self, bumps: &CloseSaleBumps) -> Result<()> {
+
+        require!(
+            self.mint.mint_authority != Some(self.program_sale_authority.key()).into(),
+            ErrorCode::MintAuthorityHeld
+        );
+
+        let lamports_reclaimed = self.program_token_account.get_lamports()
+            + self.sale_config.get_lamports()
+            + self.sale_stats.get_lamports()
+            + self.monthly_limits.as_ref().map_or(0, |monthly_limits| monthly_limits.get_lamports());
+
+        let vault = self.vault();
+        let leftover = self.program_token_account.amount;
+        if leftover > 0 {
+            vault.burn(leftover)?;
+        }
+        vault.close(self.authority.to_account_info())?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleClosed {
+            sale_config: self.sale_config.key(),
+            lamports_reclaimed,
+        })?;
+
+        Ok(())
+    }
+
+    /// The vault being closed
+    fn vault(&self) -> Vault<'info> {
+        Vault::new(
+            &self.token_program,
+            &self.mint,
+            &self.program_token_account,
+            &self.program_sale_authority,
+            &self.sale_config,
+        )
+    }
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::{events::*, states::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseWalletPurchase<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [WALLET_PURCHASE, buyer.key().as_ref()],
+        bump,
+        close = buyer,
+    )]
+    pub wallet_purchase: Account<'info, WalletPurchase>,
+
+    /// CHECK: The sale the record was last used in, read by `sale_over`;
+    /// closed accounts are fine
+    #[account(address = wallet_purchase.sale_config)]
+    pub sale_config: UncheckedAccount<'info>,
+}
+
+impl<'info> CloseWalletPurchase<'info> {
+    /// Close the buyer's purchase record, returning its rent. Only once it
+    /// no longer matters: nothing is locked, and the sale it was last used in
+    /// is finalized or closed, so a new record can't count the buyer again
+    /// or reset their purchase totals in a running sale.
+    pub fn close(&mut self, bumps: &CloseWalletPurchaseBumps) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        require!(
+            self.wallet_purchase.is_closable(current_timestamp, self.sale_over()?),
+            ErrorCode::WalletPurchaseInUse
+        );
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &WalletPurchaseClosed {
+            wallet: self.buyer.key(),
+            total_purchased: self.wallet_purchase.total_purchased,
+        })?;
+
+        Ok(())
+    }
+
+    /// Whether the record's sale is finalized, or closed, which only a
+    /// finalized sale can be
+    fn sale_over(&self) -> Result<bool> {
+
+        if self.sale_config.owner != &crate::ID || self.sale_config.data_is_empty() {
+            return Ok(true);
+        }
+
+        let sale_config = SaleConfig::try_deserialize(&mut &self.sale_config.try_borrow_data()?[..])?;
+
+        Ok(sale_config.state == SaleState::Finalized)
+    }
+}
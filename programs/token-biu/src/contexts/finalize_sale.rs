@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::{events::*, states::*, constants::*, error::ErrorCode, fees, vault::Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeSale<'info> {
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SALE_STATS, sale_config.key().as_ref()],
+        bump = sale_stats.bump,
+    )]
+    pub sale_stats: Box<Account<'info, SaleStats>>,
+
+    /// Passed when the sale has a schedule, to close out its last periods
+    #[account(
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub monthly_limits: Option<Box<Account<'info, MonthlyLimits>>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub program_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> FinalizeSale<'info> {
+    /// Wind an ended sale down: return the unsold vault tokens to the
+    /// treasury or burn them, per the sale's unsold policy
+    pub fn finalize(&mut self, bumps: &FinalizeSaleBumps, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        if let Some(old_state) = self.sale_config.sync_state(current_timestamp) {
+            emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleStateChanged {
+                old_state,
+                new_state: self.sale_config.state,
+                timestamp: current_timestamp,
+            })?;
+        }
+        let old_state = self.sale_config.transition(SaleState::Finalized)?;
+
+        let unsold_tokens = self.program_token_account.amount;
+        let unsold_policy = self.sale_config.unsold_policy;
+        let transfer_fee = match unsold_policy {
+            UnsoldPolicy::ReturnToTreasury => self.return_to_treasury(unsold_tokens, remaining_accounts)?,
+            UnsoldPolicy::Burn => self.burn_unsold(unsold_tokens)?,
+        };
+        self.sale_config.release(unsold_tokens)?;
+
+        self.close_periods(current_timestamp, bumps)?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleStateChanged {
+            old_state,
+            new_state: SaleState::Finalized,
+            timestamp: current_timestamp,
+        })?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleFinalized {
+            sol_raised: self.sale_stats.lamports_raised,
+            tokens_sold: self.sale_stats.tokens_sold,
+            tokens_minted: self.sale_config.tokens_minted,
+            unsold_tokens,
+            unsold_policy,
+            transfer_fee,
+        })?;
+
+        Ok(())
+    }
+
+    /// Emit `PeriodClosed` for the schedule's periods no unlock closed out,
+    /// the final one included
+    fn close_periods(&self, current_timestamp: i64, bumps: &FinalizeSaleBumps) -> Result<()> {
+
+        let Some(monthly_limits) = &self.monthly_limits else {
+            return Ok(());
+        };
+
+        for period in monthly_limits.unclosed_periods(current_timestamp) {
+            emit_event_cpi(&self.event_authority, bumps.event_authority, &PeriodClosed {
+                period_index: period as u16,
+                tokens_sold: monthly_limits.period_tokens_sold[period],
+                sol_raised: monthly_limits.period_sol_raised[period],
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Send the whole vault to the authority; returns the transfer fee
+    /// withheld on the way
+    fn return_to_treasury(&self, unsold_tokens: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+
+        if unsold_tokens == 0 {
+            return Ok(0);
+        }
+
+        self.vault().send(self.treasury_token_account.to_account_info(), unsold_tokens, remaining_accounts)?;
+
+        fees::transfer_fee(&self.mint.to_account_info(), unsold_tokens)
+    }
+
+    /// Burn the whole vault
+    fn burn_unsold(&self, unsold_tokens: u64) -> Result<u64> {
+
+        if unsold_tokens == 0 {
+            return Ok(0);
+        }
+
+        self.vault().burn(unsold_tokens)?;
+
+        Ok(0)
+    }
+
+    /// The vault being wound down
+    fn vault(&self) -> Vault<'info> {
+        Vault::new(
+            &self.token_program,
+            &self.mint,
+            &self.program_token_account,
+            &self.program_sale_authority,
+            &self.sale_config,
+        )
+    }
+}
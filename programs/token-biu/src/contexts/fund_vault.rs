@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::{events::*, states::*, constants::*, error::ErrorCode, fees, vault::Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundVault<'info> {
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub program_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> FundVault<'info> {
+    /// Move `token_amount` from the authority's token account into the vault,
+    /// recording what arrives after any transfer fee as deposited
+    pub fn fund(
+        &mut self,
+        token_amount: u64,
+        bumps: &FundVaultBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+
+        require!(self.sale_config.state != SaleState::Finalized, ErrorCode::InvalidSaleState);
+
+        let transfer_fee = fees::transfer_fee(&self.mint.to_account_info(), token_amount)?;
+        require!(token_amount > transfer_fee, ErrorCode::InvalidDepositAmount);
+
+        self.vault().deposit(
+            self.funder_token_account.to_account_info(),
+            self.authority.to_account_info(),
+            token_amount,
+            remaining_accounts,
+        )?;
+
+        let deposited = token_amount - transfer_fee;
+        self.sale_config.deposit(deposited)?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &VaultFunded {
+            funder: self.authority.key(),
+            token_amount: deposited,
+            transfer_fee,
+            tokens_deposited: self.sale_config.tokens_deposited,
+        })?;
+
+        Ok(())
+    }
+
+    /// The vault being funded
+    fn vault(&self) -> Vault<'info> {
+        Vault::new(
+            &self.token_program,
+            &self.mint,
+            &self.program_token_account,
+            &self.program_sale_authority,
+            &self.sale_config,
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{states::*, events::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GenerateMonthlyLimits<'info> {
+
+    #[account(
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+        realloc = MonthlyLimits::space(GENERATED_PERIODS),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> GenerateMonthlyLimits<'info> {
+    pub fn generate_limits(
+        &mut self,
+        start_time: i64,
+        period_unlock: u64,
+        treasury_unlock: u64,
+        bulk_unlock: u64,
+        bumps: &GenerateMonthlyLimitsBumps,
+    ) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        require!(
+            !self.monthly_limits.has_started(current_timestamp),
+            ErrorCode::ScheduleAlreadyStarted
+        );
+
+        let (limits, treasury_limits, timestamps) = MonthlyLimits::generate_schedule(
+            start_time,
+            self.monthly_limits.period,
+            period_unlock,
+            treasury_unlock,
+            bulk_unlock,
+        )?;
+        MonthlyLimits::validate_schedule(&limits, &treasury_limits, &timestamps)?;
+
+        self.monthly_limits.set_schedule(limits.clone(), treasury_limits.clone(), timestamps.clone());
+        self.monthly_limits.require_funded(
+            self.sale_config.token_capacity(self.sale_config.deposited_balance(), self.mint.supply),
+        )?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &MonthlyLimitsSet {
+            limits,
+            treasury_limits,
+            timestamps,
+        })?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &VestingEnabled {
+            vesting: true,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::{states::*, constants::*, error::ErrorCode};
+
+#[derive(Accounts)]
+#[instruction(periods: u16)]
+pub struct InitializeMonthlyLimits<'info> {
+
+    #[account(has_one = authority)]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MonthlyLimits::space(periods as usize),
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeMonthlyLimits<'info> {
+    /// Create the schedule account with room for `periods` periods of
+    /// length `period`; it stays empty, with vesting disabled, until a
+    /// schedule is set
+    pub fn initialize(&mut self, periods: u16, period: Period) -> Result<()> {
+
+        require!(periods as usize <= MAX_PERIODS, ErrorCode::ScheduleTooLong);
+        require!(period.is_valid(), ErrorCode::InvalidPeriod);
+
+        self.monthly_limits.is_vesting_enabled = false;
+        self.monthly_limits.period = period;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    
-    token::{Mint},
-};
+use anchor_spl::token_interface::Mint;
 use crate::{constants::*, events::*, states::*};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeSale<'info> {
 
@@ -18,19 +16,31 @@ pub struct InitializeSale<'info> {
     )]
     pub sale_config: Account<'info, SaleConfig>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = SALE_STATS_SIZE,
+        seeds = [SALE_STATS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub sale_stats: Account<'info, SaleStats>,
+
     #[account(mut)]
     pub recipient: SystemAccount<'info>,
 
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     pub system_program: Program<'info, System>,
 
 }
 
 impl<'info> InitializeSale<'info> {
-    pub fn initialize(&mut self, token_price_usd: f64, mint_decimals: u64, purchase_limit: u64) -> Result<()> {
+    pub fn initialize(&mut self, token_price_usd: f64, mint_decimals: u64, purchase_limit: u64, bumps: &InitializeSaleBumps) -> Result<()> {
 
-        let (sale_authority, bump) = Pubkey::find_program_address(&[SALE_AUTHORITY], &crate::ID);
+        let (sale_authority, bump) = Pubkey::find_program_address(
+            &[SALE_AUTHORITY, self.sale_config.key().as_ref()],
+            &crate::ID,
+        );
         let sale_config = &mut self.sale_config;
 
         sale_config.authority = self.authority.key();
@@ -42,12 +52,33 @@ impl<'info> InitializeSale<'info> {
         sale_config.token_mint = self.token_mint.key();
         sale_config.bump = bump;
         sale_config.wallet_purchase_limit = purchase_limit;
+        sale_config.lockup_duration = 0;
+        sale_config.mint_on_purchase = false;
+        sale_config.max_supply = 0;
+        sale_config.tokens_minted = 0;
+        sale_config.state = SaleState::Draft;
+        sale_config.start_time = 0;
+        sale_config.end_time = 0;
+        sale_config.unsold_policy = UnsoldPolicy::default();
+        sale_config.tokens_deposited = 0;
+        sale_config.tokens_released = 0;
+
+        self.sale_stats.set_inner(SaleStats {
+            sale_config: sale_config.key(),
+            lamports_raised: 0,
+            usd_raised: 0.0,
+            tokens_sold: 0,
+            unique_buyers: 0,
+            purchase_count: 0,
+            largest_purchase: 0,
+            bump: bumps.sale_stats,
+        });
 
-        emit!(SaleInitialized {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleInitialized {
             authority: sale_config.authority,
             token_price: token_price_usd,
             recipient: sale_config.recipient,
-        });
+        })?;
 
         Ok(())
     }
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::{states::*, constants::*, rent};
+
+#[derive(Accounts)]
+pub struct MigrateWalletPurchase<'info> {
+
+    /// CHECK: `wallet`'s record in any layout; read and rewritten by `migrate`
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [WALLET_PURCHASE, wallet.key().as_ref()],
+        bump,
+    )]
+    pub wallet_purchase: UncheckedAccount<'info>,
+
+    /// CHECK: Only its key, which seeds the record, is used
+    pub wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateWalletPurchase<'info> {
+    /// Rewrite a record created by an earlier program version in the current
+    /// layout, sized for its locks, with `payer` topping up the rent.
+    /// Records already in the current layout are left as they are.
+    pub fn migrate(&mut self) -> Result<()> {
+
+        let account = self.wallet_purchase.to_account_info();
+
+        let wallet_purchase = {
+            let data = account.try_borrow_data()?;
+            if !WalletPurchase::is_legacy(&data) {
+                return Ok(());
+            }
+            WalletPurchase::read(&data)?
+        };
+
+        rent::resize(
+            &account,
+            &self.payer.to_account_info(),
+            &self.system_program.to_account_info(),
+            WalletPurchase::space(wallet_purchase.locks.len()),
+        )?;
+
+        wallet_purchase.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
@@ -1,13 +1,45 @@
 mod admin_control;
+mod advance_schedule;
+mod amend_schedule;
+mod append_monthly_limits;
 mod buy_tokens;
+mod close_sale;
+mod close_wallet_purchase;
+mod finalize_sale;
+mod fund_vault;
+mod generate_monthly_limits;
+mod initialize_monthly_limits;
 mod initialize_sale;
+mod migrate_wallet_purchase;
+mod quote_buy;
+mod return_mint_authority;
+mod sale_mode;
+mod schedule_policy;
 mod set_monthly_limits;
+mod sync_sale_state;
 mod vesting_controls;
+mod wallet_status;
 mod withdraw_tokens;
 
 pub use admin_control::*;
+pub use advance_schedule::*;
+pub use amend_schedule::*;
+pub use append_monthly_limits::*;
 pub use buy_tokens::*;
+pub use close_sale::*;
+pub use close_wallet_purchase::*;
+pub use finalize_sale::*;
+pub use fund_vault::*;
+pub use generate_monthly_limits::*;
+pub use initialize_monthly_limits::*;
 pub use initialize_sale::*;
+pub use migrate_wallet_purchase::*;
+pub use quote_buy::*;
+pub use return_mint_authority::*;
+pub use sale_mode::*;
+pub use schedule_policy::*;
 pub use set_monthly_limits::*;
+pub use sync_sale_state::*;
 pub use vesting_controls::*;
+pub use wallet_status::*;
 pub use withdraw_tokens::*;
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::{states::*, constants::*, error::ErrorCode, fees, pricing};
+
+#[derive(Accounts)]
+pub struct QuoteBuy<'info> {
+
+    #[account(
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub program_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the buyer's `WalletPurchase` PDA, which may not exist yet;
+    /// checked and read by `WalletPurchase::load`
+    pub wallet_purchase: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
+
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> QuoteBuy<'info> {
+    /// Run `buy_tokens`' pricing and limit checks for `buyer` spending
+    /// `sol_amount` lamports, without moving anything; meant for
+    /// `simulateTransaction`
+    pub fn quote(&self, sol_amount: u64, buyer: Pubkey) -> Result<BuyQuote> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        require!(!self.sale_config.paused, ErrorCode::SalePaused);
+        self.sale_config.require_open(current_timestamp)?;
+
+        let sol_price_usd = pricing::sol_price_usd(&self.price_update)?;
+        let token_amount = self.sale_config.token_amount(sol_amount, sol_price_usd);
+        require!(token_amount > 0, ErrorCode::PurchaseTooSmall);
+
+        let transfer_amount = self.transfer_amount(token_amount)?;
+
+        let wallet_remaining = self.wallet_remaining(buyer, token_amount, current_timestamp)?;
+        let period_remaining = self.period_remaining(token_amount, current_timestamp)?;
+
+        Ok(BuyQuote {
+            token_amount,
+            sol_price_usd,
+            transfer_fee: transfer_amount - token_amount,
+            wallet_remaining,
+            period_remaining,
+        })
+    }
+
+    /// Tokens the purchase takes from the vault, or mints, as `buy_tokens`
+    /// would, checking there are enough
+    fn transfer_amount(&self, token_amount: u64) -> Result<u64> {
+
+        if self.sale_config.mint_on_purchase {
+            let supply = self.mint.supply.checked_add(token_amount).ok_or(ErrorCode::MathOverflow)?;
+            require!(supply <= self.sale_config.max_supply, ErrorCode::MaxSupplyExceeded);
+
+            return Ok(token_amount);
+        }
+
+        let transfer_amount = fees::gross_amount(&self.mint.to_account_info(), token_amount)?;
+
+        require!(self.program_token_account.amount >= transfer_amount, ErrorCode::InsufficientTokens);
+
+        Ok(transfer_amount)
+    }
+
+    /// Wallet limit left after the purchase, as checked by `buy_tokens`
+    fn wallet_remaining(&self, buyer: Pubkey, token_amount: u64, current_timestamp: i64) -> Result<u64> {
+
+        let purchased = WalletPurchase::load(&self.wallet_purchase, &buyer)?
+            .map_or(DEFAULT, |purchase| purchase.window_purchased(current_timestamp));
+
+        let total = purchased.checked_add(token_amount).ok_or(ErrorCode::MathOverflow)?;
+        require!(total <= self.sale_config.wallet_purchase_limit, ErrorCode::PurchaseLimitExceeded);
+
+        Ok(self.sale_config.wallet_purchase_limit - total)
+    }
+
+    /// Sale allowance left after the purchase, drawn from a copy of the
+    /// schedule so periods unlock exactly as they would on purchase
+    fn period_remaining(&self, token_amount: u64, current_timestamp: i64) -> Result<Option<u64>> {
+
+        if !self.monthly_limits.is_vesting_enabled {
+            return Ok(None);
+        }
+
+        let mut monthly_limits = MonthlyLimits::clone(&self.monthly_limits);
+        monthly_limits.draw(
+            Allowance::Sale,
+            token_amount,
+            current_timestamp,
+            self.sale_config.token_capacity(self.program_token_account.amount, self.mint.supply),
+        )?;
+
+        Ok(Some(monthly_limits.sale_available))
+    }
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::instruction::AuthorityType,
+    token_interface::{self, Mint, TokenInterface},
+};
+use crate::{events::*, states::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReturnMintAuthority<'info> {
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Account<'info, SaleConfig>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ReturnMintAuthority<'info> {
+    /// Hand the mint authority held by this sale's authority PDA to
+    /// `new_authority`, turning minting on purchase off first if it is on
+    pub fn return_authority(&mut self, new_authority: Pubkey, bumps: &ReturnMintAuthorityBumps) -> Result<()> {
+
+        require!(
+            self.mint.mint_authority == Some(self.program_sale_authority.key()).into(),
+            ErrorCode::WrongMintAuthority
+        );
+
+        if self.sale_config.mint_on_purchase {
+            self.sale_config.mint_on_purchase = false;
+
+            emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleModeSet {
+                mint_on_purchase: false,
+                max_supply: self.sale_config.max_supply,
+            })?;
+        }
+
+        let sale_config = self.sale_config.key();
+        let authority_seeds: &[&[u8]] = &[
+            SALE_AUTHORITY,
+            sale_config.as_ref(),
+            &[self.sale_config.bump],
+        ];
+
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token_interface::SetAuthority {
+                    current_authority: self.program_sale_authority.to_account_info(),
+                    account_or_mint: self.mint.to_account_info(),
+                },
+                &[authority_seeds]
+            ),
+            AuthorityType::MintTokens,
+            Some(new_authority),
+        )?;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &MintAuthorityReturned {
+            new_authority,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::{events::*, states::*, constants::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetSaleMode<'info> {
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Account<'info, SaleConfig>,
+
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+}
+
+impl<'info> SetSaleMode<'info> {
+    /// Sell by minting each purchase, up to `max_supply` total supply, or
+    /// from the pre-funded vault. Minting needs this sale's authority PDA to
+    /// be the mint authority.
+    pub fn set_mode(&mut self, mint_on_purchase: bool, max_supply: u64, bumps: &SetSaleModeBumps) -> Result<()> {
+
+        if mint_on_purchase {
+            require!(
+                self.mint.mint_authority == Some(self.program_sale_authority.key()).into(),
+                ErrorCode::WrongMintAuthority
+            );
+            require!(max_supply >= self.mint.supply, ErrorCode::InvalidMaxSupply);
+        }
+
+        self.sale_config.mint_on_purchase = mint_on_purchase;
+        self.sale_config.max_supply = max_supply;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleModeSet {
+            mint_on_purchase,
+            max_supply,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::{events::*, states::*, constants::*};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetSchedulePolicy<'info> {
+
+    #[account(has_one = authority)]
+    pub sale_config: Account<'info, SaleConfig>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub monthly_limits: Account<'info, MonthlyLimits>,
+}
+
+impl<'info> SetSchedulePolicy<'info> {
+    /// Choose what happens to unsold sale allocations when a period ends and
+    /// what unlocks after the final period; applies from the next period on
+    pub fn set_policy(&mut self, carry_over: CarryOver, tail_policy: TailPolicy, bumps: &SetSchedulePolicyBumps) -> Result<()> {
+
+        self.monthly_limits.carry_over = carry_over;
+        self.monthly_limits.tail_policy = tail_policy;
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SchedulePolicySet {
+            carry_over,
+            tail_policy,
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,47 +1,75 @@
 use anchor_lang::prelude::*;
-use crate::{states::*, events::*, constants::*};
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{states::*, events::*, constants::*, error::ErrorCode};
 
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(limits: Vec<u64>)]
 pub struct SetMonthlyLimits<'info> {
 
-    #[account(mut, has_one = authority)]
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
     pub sale_config: Box<Account<'info, SaleConfig>>,
 
     #[account(
-        init_if_needed,
-        payer = authority,
-        space = MONTHLY_LIMITS_SIZE,
-        seeds = [b"monthly_limits_a"],
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
         bump,
+        realloc = MonthlyLimits::space(limits.len()),
+        realloc::payer = authority,
+        realloc::zero = false,
     )]
     pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> SetMonthlyLimits<'info> {
-    pub fn set_limits(&mut self, limits: [u64; 14], timestamps: [i64; 14]) -> Result<()> {
+    /// Replace the whole schedule, restarting its accounting.
+    ///
+    /// Only allowed before the current schedule has started; after that,
+    /// future periods are changed with `amend_schedule`.
+    pub fn set_limits(
+        &mut self,
+        limits: Vec<u64>,
+        treasury_limits: Vec<u64>,
+        timestamps: Vec<i64>,
+        bumps: &SetMonthlyLimitsBumps,
+    ) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
 
+        require!(
+            !self.monthly_limits.has_started(current_timestamp),
+            ErrorCode::ScheduleAlreadyStarted
+        );
+        MonthlyLimits::validate_schedule(&limits, &treasury_limits, &timestamps)?;
 
-        self.monthly_limits.limits = limits;
-        self.monthly_limits.timestamps = timestamps;
-        self.monthly_limits.is_vesting_enabled = true;
-        self.monthly_limits.tokens_unlocked = DEFAULT;
-        self.monthly_limits.tokens_available = DEFAULT;
-        self.monthly_limits.last_checked_index = DEFAULT as u8;
-        self.monthly_limits.tokens_withdrawn = DEFAULT;
+        self.monthly_limits.set_schedule(limits.clone(), treasury_limits.clone(), timestamps.clone());
+        self.monthly_limits.require_funded(
+            self.sale_config.token_capacity(self.sale_config.deposited_balance(), self.mint.supply),
+        )?;
 
-        emit!(MonthlyLimitsSet {
-            limits: self.monthly_limits.limits,
-            timestamps: self.monthly_limits.timestamps,
-        });
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &MonthlyLimitsSet {
+            limits,
+            treasury_limits,
+            timestamps,
+        })?;
 
-        emit!(VestingEnabled {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &VestingEnabled {
             vesting: true,
-        });
+        })?;
 
         Ok(())
     }
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::{events::*, states::*, error::ErrorCode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SyncSaleState<'info> {
+
+    #[account(mut)]
+    pub sale_config: Account<'info, SaleConfig>,
+}
+
+impl<'info> SyncSaleState<'info> {
+    /// Record an end time that has passed. Permissionless: purchases already
+    /// stop at the end time, this only makes the state and its event visible.
+    pub fn sync(&mut self, bumps: &SyncSaleStateBumps) -> Result<()> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        let Some(old_state) = self.sale_config.sync_state(current_timestamp) else {
+            return err!(ErrorCode::SaleStateUnchanged);
+        };
+
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleStateChanged {
+            old_state,
+            new_state: self.sale_config.state,
+            timestamp: current_timestamp,
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 
 use anchor_lang::prelude::*;
-use crate::{events::*, states::* };
+use crate::{events::*, states::*, constants::*};
 
+#[event_cpi]
 #[derive(Accounts)]
 
     pub struct VestingControl<'info> {
@@ -11,31 +12,35 @@ use crate::{events::*, states::* };
 
     pub authority: Signer<'info>,
 
-    
-    #[account(mut)]
+
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
     pub monthly_limits: Account<'info, MonthlyLimits>,
 
 }
 
 impl<'info> VestingControl<'info> {
-    pub fn enable_vesting(&mut self) -> Result<()> {
+    pub fn enable_vesting(&mut self, bumps: &VestingControlBumps) -> Result<()> {
 
         self.monthly_limits.is_vesting_enabled = true;
         
-        emit!(VestingEnabled {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &VestingEnabled {
             vesting: true,
-        });
+        })?;
 
         Ok(())
     }
 
-    pub fn disable_vesting(&mut self) -> Result<()> {
+    pub fn disable_vesting(&mut self, bumps: &VestingControlBumps) -> Result<()> {
 
         self.monthly_limits.is_vesting_enabled = false;
 
-        emit!(VestingDisabled {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &VestingDisabled {
             vesting: false,
-        });
+        })?;
 
         Ok(())
     }
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{states::*, constants::*, error::ErrorCode};
+
+#[derive(Accounts)]
+pub struct WalletStatusView<'info> {
+
+    #[account(
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
+    pub sale_config: Box<Account<'info, SaleConfig>>,
+
+    #[account(
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
+    )]
+    pub program_sale_authority: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub program_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the wallet's `WalletPurchase` PDA, which may not exist yet;
+    /// checked and read by `WalletPurchase::load`
+    pub wallet_purchase: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
+    pub monthly_limits: Box<Account<'info, MonthlyLimits>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WalletStatusView<'info> {
+    /// How much `wallet` can still buy, with the daily window counted the
+    /// way `buy_tokens` counts it; meant for `simulateTransaction`
+    pub fn status(&self, wallet: Pubkey) -> Result<WalletStatus> {
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        let purchase = WalletPurchase::load(&self.wallet_purchase, &wallet)?;
+        let window_purchased = purchase
+            .as_ref()
+            .map_or(DEFAULT, |purchase| purchase.window_purchased(current_timestamp));
+
+        let period_remaining = if self.monthly_limits.is_vesting_enabled {
+            Some(self.monthly_limits.sale_allowance_at(
+                current_timestamp,
+                self.sale_config.token_capacity(self.program_token_account.amount, self.mint.supply),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(WalletStatus {
+            window_purchased,
+            window_resets_at: purchase
+                .as_ref()
+                .and_then(|purchase| purchase.window_resets_at(current_timestamp)),
+            lifetime_purchased: purchase.as_ref().map_or(DEFAULT, |purchase| purchase.lifetime_purchased),
+            wallet_remaining: self.sale_config.wallet_purchase_limit.saturating_sub(window_purchased),
+            period_remaining,
+        })
+    }
+}
@@ -1,46 +1,61 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use crate::{events::*, states::*, constants::*, error::ErrorCode};
+use crate::{events::*, states::*, constants::*, error::ErrorCode, fees, vault::{self, Vault}};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithdrawTokens<'info> {
 
-    #[account(has_one = authority)]
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = sale_config.token_mint == mint.key() @ ErrorCode::InvalidTokenMint
+    )]
     pub sale_config: Account<'info, SaleConfig>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [MONTHLY_LIMITS, sale_config.key().as_ref()],
+        bump,
+    )]
     pub monthly_limits: Account<'info, MonthlyLimits>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = program_sale_authority
+        associated_token::authority = program_sale_authority,
+        associated_token::token_program = token_program,
     )]
-    pub program_token_account: Account<'info, TokenAccount>,
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
-            seeds = [SALE_AUTHORITY],
-            bump = sale_config.bump,
+        seeds = [SALE_AUTHORITY, sale_config.key().as_ref()],
+        bump = sale_config.bump,
     )]
     pub program_sale_authority: SystemAccount<'info>,
 
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
         payer = authority,
         associated_token::mint = mint,
         associated_token::authority = authority,
+        associated_token::token_program = token_program,
     )]
-    pub admin_token_account: Account<'info, TokenAccount>,
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
 
@@ -49,107 +64,96 @@ pub struct WithdrawTokens<'info> {
 }
 
 impl<'info> WithdrawTokens<'info> {
-    pub fn withdraw_remaining_tokens(&mut self, token_amount: u64) -> Result<()>{
+    pub fn withdraw_remaining_tokens(
+        &mut self,
+        token_amount: u64,
+        bumps: &WithdrawTokensBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()>{
 
         let current_timestamp = Clock::get()?.unix_timestamp;
 
-        self.check_monthly_limits(token_amount, current_timestamp)?;
+        self.sync_sale_state(current_timestamp, bumps)?;
+        require!(
+            matches!(self.sale_config.state, SaleState::Active | SaleState::Ended),
+            ErrorCode::InvalidSaleState
+        );
 
-        self.transfer_tokens_to_admin(token_amount)?;
+        let advance = self.draw_treasury_allowance(token_amount, current_timestamp)?;
+        let transfer_amount = fees::gross_amount(&self.mint.to_account_info(), token_amount)?;
 
-        self.update_state(token_amount)?;
+        self.transfer_tokens_to_admin(transfer_amount, remaining_accounts)?;
+        self.sale_config.release(transfer_amount)?;
+        emit_advance(&self.event_authority, bumps.event_authority, &self.monthly_limits, &advance)?;
+        self.burn_unsold_allocation(bumps)?;
 
-        emit!(AdminWithdrawnTokens {
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &AdminWithdrawnTokens {
             tokens_withdrawn: token_amount,
-        });
-
-        Ok(())
-
-    }
+            transfer_fee: transfer_amount - token_amount,
+        })?;
 
-    /// Transfer tokens from program to admin
-    fn transfer_tokens_to_admin(&self, token_amount: u64) -> Result<()> {
-
-        let authority_seeds: &[&[u8]] = &[
-            SALE_AUTHORITY,
-            &[self.sale_config.bump],
-        ];
-
-        anchor_spl::token::transfer(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: self.program_token_account.to_account_info(),
-                    to: self.admin_token_account.to_account_info(),
-                    authority: self.program_sale_authority.to_account_info(),
-                },
-                &[authority_seeds]
-            ),
-            token_amount
-        )?;
+        emit_event_cpi(&self.event_authority, bumps.event_authority, &TreasuryAllowanceUsed {
+            token_amount,
+            treasury_available: self.monthly_limits.treasury_available,
+            tokens_withdrawn: self.monthly_limits.tokens_withdrawn,
+        })?;
 
         Ok(())
-    }
-
-    /// Update state after purchase
-    fn update_state(&mut self, token_amount: u64) -> Result<()> {
-
-        self.monthly_limits.tokens_unlocked += token_amount;
-        self.monthly_limits.tokens_withdrawn += token_amount;
-        self.monthly_limits.tokens_available -= token_amount;
 
-        Ok(())
     }
 
+    /// Record an end time that has passed
+    fn sync_sale_state(&mut self, current_timestamp: i64, bumps: &WithdrawTokensBumps) -> Result<()> {
 
-    /// Check monthly limits
-    fn check_monthly_limits(&mut self, token_amount: u64, current_timestamp: i64) -> Result<()> {
-
-        let timestamps = self.monthly_limits.timestamps;
-        let limits = self.monthly_limits.limits;
-
-        if self.monthly_limits.last_checked_index  as usize >= timestamps.len() - 1{
-            self.monthly_limits.tokens_available = self.program_token_account.amount;
-            return Ok(());
+        if let Some(old_state) = self.sale_config.sync_state(current_timestamp) {
+            emit_event_cpi(&self.event_authority, bumps.event_authority, &SaleStateChanged {
+                old_state,
+                new_state: self.sale_config.state,
+                timestamp: current_timestamp,
+            })?;
         }
 
+        Ok(())
+    }
 
+    /// Transfer tokens from program to admin
+    fn transfer_tokens_to_admin(&self, transfer_amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
 
-        if current_timestamp <= self.monthly_limits.timestamps[self.monthly_limits.last_checked_index  as usize + 1]{
-
-            if current_timestamp < self.monthly_limits.timestamps[DEFAULT as usize]{
-                return Err(ErrorCode::SaleNotStarted.into());
-            }
-
-            if self.monthly_limits.tokens_available == 0 && self.monthly_limits.tokens_unlocked == 0{
-                self.monthly_limits.tokens_available = limits[self.monthly_limits.last_checked_index as usize];
-            }
-
-
-        require!(
-            token_amount <= self.monthly_limits.tokens_available,
-            ErrorCode::MonthlyLimitExceeded
-        );
+        self.vault().send(self.admin_token_account.to_account_info(), transfer_amount, remaining_accounts)
+    }
 
-            Ok(())
-        }
+    /// The vault withdrawals come out of
+    fn vault(&self) -> Vault<'info> {
+        Vault::new(
+            &self.token_program,
+            &self.mint,
+            &self.program_token_account,
+            &self.program_sale_authority,
+            &self.sale_config,
+        )
+    }
 
-        else{
-            let mut i = self.monthly_limits.last_checked_index as usize + 1;
-            let mut temp_var  = self.monthly_limits.last_checked_index as usize;
+    /// Burn the unsold allocation the carry-over policy sent to be burned
+    fn burn_unsold_allocation(&mut self, bumps: &WithdrawTokensBumps) -> Result<()> {
+
+        vault::burn_unsold_allocation(
+            &self.vault(),
+            self.program_token_account.amount,
+            &mut self.sale_config,
+            &mut self.monthly_limits,
+            &self.event_authority,
+            bumps.event_authority,
+        )
+    }
 
-            while i < timestamps.len() && current_timestamp >= timestamps[i]{
-                temp_var = i;
-                self.monthly_limits.tokens_available += limits[i];
-                i += 1;
-            }
-            self.monthly_limits.last_checked_index = temp_var as u8;
+    /// Take the withdrawal out of the schedule's treasury allowance
+    fn draw_treasury_allowance(&mut self, token_amount: u64, current_timestamp: i64) -> Result<Advance> {
 
-        require!(
-            token_amount <= self.monthly_limits.tokens_available,
-            ErrorCode::MonthlyLimitExceeded
-        );
-            Ok(())
-        }
-   }
+        self.monthly_limits.draw(
+            Allowance::Treasury,
+            token_amount,
+            current_timestamp,
+            self.sale_config.token_capacity(self.program_token_account.amount, self.mint.supply),
+        )
+    }
 }
@@ -0,0 +1,50 @@
+//! Helpers for programs buying through CPI, such as aggregators buying for
+//! a PDA they control: the addresses `buy_tokens` checks, and a wrapper
+//! that signs for PDA buyers or payers and forwards transfer-hook accounts.
+
+use anchor_lang::prelude::*;
+use crate::{constants::*, cpi};
+
+/// `SALE_AUTHORITY` PDA of `sale_config`, which owns its token vault
+pub fn program_sale_authority(sale_config: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SALE_AUTHORITY, sale_config.as_ref()], &crate::ID).0
+}
+
+/// Anchor's event-CPI authority, passed as `event_authority`
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY], &crate::ID).0
+}
+
+/// `buyer`'s `WalletPurchase` record
+pub fn wallet_purchase(buyer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[WALLET_PURCHASE, buyer.as_ref()], &crate::ID).0
+}
+
+/// The `MonthlyLimits` vesting schedule of `sale_config`
+pub fn monthly_limits(sale_config: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MONTHLY_LIMITS, sale_config.as_ref()], &crate::ID).0
+}
+
+/// `SaleStats` purchase totals of `sale_config`
+pub fn sale_stats(sale_config: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SALE_STATS, sale_config.as_ref()], &crate::ID).0
+}
+
+/// Buy `sol_amount` lamports' worth of tokens through CPI. `signer_seeds`
+/// sign for a buyer, payer or beneficiary that is a PDA of the calling
+/// program, and `remaining_accounts` carry the extra accounts of a transfer
+/// hook. The purchase's `TokensPurchased` event records the calling program.
+pub fn buy_tokens<'info>(
+    token_biu_program: AccountInfo<'info>,
+    accounts: cpi::accounts::BuyTokens<'info>,
+    sol_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+    remaining_accounts: Vec<AccountInfo<'info>>,
+) -> Result<()> {
+
+    cpi::buy_tokens(
+        CpiContext::new_with_signer(token_biu_program, accounts, signer_seeds)
+            .with_remaining_accounts(remaining_accounts),
+        sol_amount,
+    )
+}
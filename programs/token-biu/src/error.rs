@@ -47,5 +47,95 @@ pub enum ErrorCode {
 
 	  #[msg("The Withdraw Limit has exceeded")]
     WithdrawLimitExceeded,
+
+    #[msg("Schedule unlocks more tokens than were deposited in the vault")]
+    ScheduleExceedsVault,
+
+    #[msg("Schedule needs exactly one limit per timestamp")]
+    ScheduleLengthMismatch,
+
+    #[msg("Schedule has no periods")]
+    EmptySchedule,
+
+    #[msg("Schedule has more periods than an account can track")]
+    ScheduleTooLong,
+
+    #[msg("Schedule timestamps are out of order")]
+    InvalidScheduleOrder,
+
+    #[msg("Schedule has started, amend future periods instead")]
+    ScheduleAlreadyStarted,
+
+    #[msg("Period has already been reached")]
+    PeriodAlreadyReached,
+
+    #[msg("Amendment starts past the end of the schedule")]
+    InvalidAmendIndex,
+
+    #[msg("Treasury Limit Exceeded")]
+    TreasuryLimitExceeded,
+
+    #[msg("No new period has been reached")]
+    NothingToAdvance,
+
+    #[msg("Period length must be positive")]
+    InvalidPeriod,
+
+    #[msg("Calendar schedules must start on the 1st of a month, 00:00 UTC")]
+    InvalidStartTime,
+
+    #[msg("Lockup duration can't be negative")]
+    InvalidLockupDuration,
+
+    #[msg("Sale authority PDA is not the mint authority")]
+    WrongMintAuthority,
+
+    #[msg("Max supply is below the current supply")]
+    InvalidMaxSupply,
+
+    #[msg("Purchase would mint past the max supply")]
+    MaxSupplyExceeded,
+
+    #[msg("Sale has not been activated")]
+    SaleNotActive,
+
+    #[msg("Sale has ended")]
+    SaleEnded,
+
+    #[msg("Sale end time must be after its start time")]
+    InvalidSaleWindow,
+
+    #[msg("Sale can't move to that state")]
+    InvalidStateTransition,
+
+    #[msg("Not allowed in the sale's current state")]
+    InvalidSaleState,
+
+    #[msg("Sale state is already up to date")]
+    SaleStateUnchanged,
+
+    #[msg("Sale must be finalized first")]
+    SaleNotFinalized,
+
+    #[msg("Purchase record is still needed")]
+    WalletPurchaseInUse,
+
+    #[msg("Deposit must be more than the transfer fee")]
+    InvalidDepositAmount,
+
+    #[msg("Not the wallet's purchase record")]
+    InvalidWalletPurchase,
+
+    #[msg("The sale's vault can't be a purchase beneficiary")]
+    InvalidBeneficiary,
+
+    #[msg("Too many purchases are still locked in this wallet")]
+    TooManyLocks,
+
+    #[msg("The sale still holds the mint authority")]
+    MintAuthorityHeld,
+
+    #[msg("Purchase is too small to buy any tokens")]
+    PurchaseTooSmall,
 }
 
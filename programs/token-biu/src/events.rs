@@ -1,4 +1,80 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    event::EVENT_IX_TAG_LE,
+    Event,
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+use crate::{constants::*, states::{Advance, CarryOver, MonthlyLimits, SaleState, TailPolicy, UnsoldPolicy}};
+
+/// Emit an event through a self-CPI signed by the `event_authority` PDA.
+///
+/// Same instruction `emit_cpi!` builds, but usable from the account-struct
+/// handlers which don't have a `ctx` in scope. Indexers read the event from
+/// the inner instructions instead of the (truncatable) program logs.
+pub fn emit_event_cpi<E: Event>(
+    event_authority: &AccountInfo,
+    event_authority_bump: u8,
+    event: &E,
+) -> Result<()> {
+    let ix_data: Vec<u8> = EVENT_IX_TAG_LE
+        .into_iter()
+        .chain(event.data())
+        .collect();
+
+    let ix = Instruction::new_with_bytes(
+        crate::ID,
+        &ix_data,
+        vec![AccountMeta::new_readonly(event_authority.key(), true)],
+    );
+
+    invoke_signed(
+        &ix,
+        std::slice::from_ref(event_authority),
+        &[&[EVENT_AUTHORITY, &[event_authority_bump]]],
+    )?;
+
+    Ok(())
+}
+
+/// Emit `PeriodClosed` for the period ending and `PeriodUnlocked` for every
+/// period `advance` crossed, then `UnsoldAllocationExpired` if their
+/// carry-over moved anything to the treasury.
+pub fn emit_advance(
+    event_authority: &AccountInfo,
+    event_authority_bump: u8,
+    monthly_limits: &MonthlyLimits,
+    advance: &Advance,
+) -> Result<()> {
+    for index in advance.first_period..monthly_limits.periods_unlocked {
+        let period = index as usize;
+
+        if let Some(closed) = period.checked_sub(1) {
+            emit_event_cpi(event_authority, event_authority_bump, &PeriodClosed {
+                period_index: closed as u16,
+                tokens_sold: monthly_limits.period_tokens_sold[closed],
+                sol_raised: monthly_limits.period_sol_raised[closed],
+            })?;
+        }
+
+        emit_event_cpi(event_authority, event_authority_bump, &PeriodUnlocked {
+            period_index: index,
+            timestamp: monthly_limits.timestamps[period],
+            sale_limit: monthly_limits.limits[period],
+            treasury_limit: monthly_limits.treasury_limits[period],
+        })?;
+    }
+
+    if advance.expired > 0 {
+        emit_event_cpi(event_authority, event_authority_bump, &UnsoldAllocationExpired {
+            token_amount: advance.expired,
+        })?;
+    }
+
+    Ok(())
+}
 
 // Event definitions
 #[event]
@@ -11,9 +87,13 @@ pub struct SaleInitialized {
 #[event]
 pub struct TokensPurchased {
     pub buyer: Pubkey,
+    pub payer: Pubkey,
+    pub caller_program: Option<Pubkey>,
+    pub beneficiary: Pubkey,
     pub sol_amount: u64,
     pub token_amount: u64,
     pub sol_price: f64,
+    pub transfer_fee: u64,
 }
 
 #[event]
@@ -35,8 +115,26 @@ pub struct WalletLimitSet {
 	
 #[event]
 pub struct MonthlyLimitsSet {
-    pub limits: [u64; 14],
-    pub timestamps: [i64; 14],
+    pub limits: Vec<u64>,
+    pub treasury_limits: Vec<u64>,
+    pub timestamps: Vec<i64>,
+}
+
+#[event]
+pub struct MonthlyLimitsAppended {
+    pub limits: Vec<u64>,
+    pub treasury_limits: Vec<u64>,
+    pub timestamps: Vec<i64>,
+    pub total_periods: u64,
+}
+
+#[event]
+pub struct ScheduleAmended {
+    pub from_index: u64,
+    pub limits: Vec<u64>,
+    pub treasury_limits: Vec<u64>,
+    pub timestamps: Vec<i64>,
+    pub total_periods: u64,
 }
 
 #[event]
@@ -52,5 +150,130 @@ pub struct VestingDisabled {
 #[event]
 pub struct AdminWithdrawnTokens {
     pub tokens_withdrawn: u64,
+    pub transfer_fee: u64,
+}
+
+#[event]
+pub struct SchedulePolicySet {
+    pub carry_over: CarryOver,
+    pub tail_policy: TailPolicy,
+}
+
+#[event]
+pub struct PeriodClosed {
+    pub period_index: u16,
+    pub tokens_sold: u64,
+    pub sol_raised: u64,
+}
+
+#[event]
+pub struct PeriodUnlocked {
+    pub period_index: u16,
+    pub timestamp: i64,
+    pub sale_limit: u64,
+    pub treasury_limit: u64,
+}
+
+#[event]
+pub struct UnsoldAllocationExpired {
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct UnsoldAllocationBurned {
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct SaleAllowanceUsed {
+    pub token_amount: u64,
+    pub sale_available: u64,
+    pub tokens_sold: u64,
+}
+
+#[event]
+pub struct TreasuryAllowanceUsed {
+    pub token_amount: u64,
+    pub treasury_available: u64,
+    pub tokens_withdrawn: u64,
+}
+
+#[event]
+pub struct LockupDurationSet {
+    pub lockup_duration: i64,
+}
+
+#[event]
+pub struct TokensLocked {
+    pub buyer: Pubkey,
+    pub token_amount: u64,
+    pub locked_amount: u64,
+    pub unlock_timestamp: i64,
+}
+
+#[event]
+pub struct SaleModeSet {
+    pub mint_on_purchase: bool,
+    pub max_supply: u64,
+}
+
+#[event]
+pub struct MintAuthorityReturned {
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub buyer: Pubkey,
+    pub token_amount: u64,
+    pub tokens_minted: u64,
+    pub supply: u64,
 }
 
+#[event]
+pub struct SaleWindowSet {
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct SaleStateChanged {
+    pub old_state: SaleState,
+    pub new_state: SaleState,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnsoldPolicySet {
+    pub unsold_policy: UnsoldPolicy,
+}
+
+#[event]
+pub struct SaleFinalized {
+    pub sol_raised: u64,
+    pub tokens_sold: u64,
+    pub tokens_minted: u64,
+    pub unsold_tokens: u64,
+    pub unsold_policy: UnsoldPolicy,
+    pub transfer_fee: u64,
+}
+
+#[event]
+pub struct SaleClosed {
+    pub sale_config: Pubkey,
+    pub lamports_reclaimed: u64,
+}
+
+#[event]
+pub struct WalletPurchaseClosed {
+    pub wallet: Pubkey,
+    pub total_purchased: u64,
+}
+
+#[event]
+pub struct VaultFunded {
+    pub funder: Pubkey,
+    pub token_amount: u64,
+    pub transfer_fee: u64,
+    pub tokens_deposited: u64,
+}
@@ -0,0 +1,49 @@
+//! Transfer-fee handling for Token-2022 mints, so recipients get exactly the
+//! amount the sale records.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use crate::error::ErrorCode;
+
+/// Amount to send so the recipient receives `net_amount` after the mint's
+/// transfer fee for the current epoch. Mints without the transfer-fee
+/// extension, including every SPL Token mint, charge nothing.
+pub fn gross_amount(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+
+    let Ok(fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(net_amount);
+    };
+
+    let gross_amount = fee_config
+        .get_epoch_fee(Clock::get()?.epoch)
+        .calculate_pre_fee_amount(net_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // A 100% fee can't deliver anything
+    require!(gross_amount >= net_amount, ErrorCode::InvalidCalculation);
+
+    Ok(gross_amount)
+}
+
+/// Fee the mint withholds from a transfer of `amount` in the current epoch
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+
+    let Ok(fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+
+    let fee = fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(fee)
+}
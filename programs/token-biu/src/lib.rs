@@ -1,12 +1,21 @@
 use anchor_lang::prelude::*;
 
+pub mod calendar;
 pub mod constants;
 pub mod contexts;
+#[cfg(feature = "cpi")]
+pub mod cpi_helpers;
 pub mod error;
 pub mod events;
+pub mod fees;
+pub mod pricing;
+pub mod rent;
 pub mod states;
+pub mod vault;
 
 
+use constants::Period;
+use states::{BuyQuote, CarryOver, SaleState, TailPolicy, UnsoldPolicy, WalletStatus};
 use contexts::*;
 
 declare_id!("6LUVNKtbT9a86zoJRCgh8wKBNphcFexBe1uo353jS5mb");
@@ -21,23 +30,79 @@ pub mod token_biu {
         mint_decimals: u64,
         purchase_limit: u64,
     ) -> Result<()> {
-        ctx.accounts.initialize(token_price_usd, mint_decimals, purchase_limit)
+        ctx.accounts.initialize(token_price_usd, mint_decimals, purchase_limit, &ctx.bumps)
     }
 
-    pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
-        ctx.accounts.buy(sol_amount)
+    pub fn buy_tokens<'info>(ctx: Context<'_, '_, '_, 'info, BuyTokens<'info>>, sol_amount: u64) -> Result<()> {
+        ctx.accounts.buy(sol_amount, &ctx.bumps, ctx.remaining_accounts)
+    }
+
+    pub fn quote_buy(ctx: Context<QuoteBuy>, sol_amount: u64, buyer: Pubkey) -> Result<BuyQuote> {
+        ctx.accounts.quote(sol_amount, buyer)
+    }
+
+    pub fn wallet_status(ctx: Context<WalletStatusView>, wallet: Pubkey) -> Result<WalletStatus> {
+        ctx.accounts.status(wallet)
     }
 
     pub fn change_reciepent_account(ctx: Context<AdminControl>, new_receipent: Pubkey) -> Result<()> {
-        ctx.accounts.change_recipient(new_receipent)
+        ctx.accounts.change_recipient(new_receipent, &ctx.bumps)
     }
 
     pub fn change_config_authority(ctx: Context<AdminControl>, new_authority: Pubkey) -> Result<()> {
-        ctx.accounts.change_authority(new_authority)
+        ctx.accounts.change_authority(new_authority, &ctx.bumps)
     }
 
     pub fn set_purchase_limit(ctx: Context<AdminControl>, new_limit: u64) -> Result<()> {
-        ctx.accounts.set_limit(new_limit)
+        ctx.accounts.set_limit(new_limit, &ctx.bumps)
+    }
+
+    pub fn set_lockup_duration(ctx: Context<AdminControl>, lockup_duration: i64) -> Result<()> {
+        ctx.accounts.set_lockup_duration(lockup_duration, &ctx.bumps)
+    }
+
+    pub fn set_sale_mode(ctx: Context<SetSaleMode>, mint_on_purchase: bool, max_supply: u64) -> Result<()> {
+        ctx.accounts.set_mode(mint_on_purchase, max_supply, &ctx.bumps)
+    }
+
+    pub fn return_mint_authority(ctx: Context<ReturnMintAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.return_authority(new_authority, &ctx.bumps)
+    }
+
+    pub fn set_sale_window(ctx: Context<AdminControl>, start_time: i64, end_time: i64) -> Result<()> {
+        ctx.accounts.set_sale_window(start_time, end_time, &ctx.bumps)
+    }
+
+    pub fn activate_sale(ctx: Context<AdminControl>) -> Result<()> {
+        ctx.accounts.transition(SaleState::Active, &ctx.bumps)
+    }
+
+    pub fn end_sale(ctx: Context<AdminControl>) -> Result<()> {
+        ctx.accounts.transition(SaleState::Ended, &ctx.bumps)
+    }
+
+    pub fn set_unsold_policy(ctx: Context<AdminControl>, unsold_policy: UnsoldPolicy) -> Result<()> {
+        ctx.accounts.set_unsold_policy(unsold_policy, &ctx.bumps)
+    }
+
+    pub fn finalize_sale<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeSale<'info>>) -> Result<()> {
+        ctx.accounts.finalize(&ctx.bumps, ctx.remaining_accounts)
+    }
+
+    pub fn close_sale(ctx: Context<CloseSale>) -> Result<()> {
+        ctx.accounts.close(&ctx.bumps)
+    }
+
+    pub fn close_wallet_purchase(ctx: Context<CloseWalletPurchase>) -> Result<()> {
+        ctx.accounts.close(&ctx.bumps)
+    }
+
+    pub fn migrate_wallet_purchase(ctx: Context<MigrateWalletPurchase>) -> Result<()> {
+        ctx.accounts.migrate()
+    }
+
+    pub fn sync_sale_state(ctx: Context<SyncSaleState>) -> Result<()> {
+        ctx.accounts.sync(&ctx.bumps)
     }
 
     pub fn pause_sale(ctx: Context<AdminControl>) -> Result<()> {
@@ -48,19 +113,69 @@ pub mod token_biu {
         ctx.accounts.resume()
     }
 
-    pub fn set_monthly_limits(ctx: Context<SetMonthlyLimits>, limits: [u64; 14], timestamps: [i64; 14]) -> Result<()> {
-        ctx.accounts.set_limits(limits, timestamps)
+    pub fn fund_vault<'info>(ctx: Context<'_, '_, '_, 'info, FundVault<'info>>, token_amount: u64) -> Result<()> {
+        ctx.accounts.fund(token_amount, &ctx.bumps, ctx.remaining_accounts)
+    }
+
+    pub fn initialize_monthly_limits(ctx: Context<InitializeMonthlyLimits>, periods: u16, period: Period) -> Result<()> {
+        ctx.accounts.initialize(periods, period)
+    }
+
+    pub fn set_monthly_limits(
+        ctx: Context<SetMonthlyLimits>,
+        limits: Vec<u64>,
+        treasury_limits: Vec<u64>,
+        timestamps: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.set_limits(limits, treasury_limits, timestamps, &ctx.bumps)
+    }
+
+    pub fn append_monthly_limits(
+        ctx: Context<AppendMonthlyLimits>,
+        limits: Vec<u64>,
+        treasury_limits: Vec<u64>,
+        timestamps: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.append_limits(limits, treasury_limits, timestamps, &ctx.bumps)
+    }
+
+    pub fn amend_schedule(
+        ctx: Context<AmendSchedule>,
+        from_index: u16,
+        limits: Vec<u64>,
+        treasury_limits: Vec<u64>,
+        timestamps: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.amend(from_index, limits, treasury_limits, timestamps, &ctx.bumps)
+    }
+
+    pub fn generate_monthly_limits(
+        ctx: Context<GenerateMonthlyLimits>,
+        start_time: i64,
+        period_unlock: u64,
+        treasury_unlock: u64,
+        bulk_unlock: u64,
+    ) -> Result<()> {
+        ctx.accounts.generate_limits(start_time, period_unlock, treasury_unlock, bulk_unlock, &ctx.bumps)
+    }
+
+    pub fn advance_schedule(ctx: Context<AdvanceSchedule>) -> Result<()> {
+        ctx.accounts.advance(&ctx.bumps)
+    }
+
+    pub fn set_schedule_policy(ctx: Context<SetSchedulePolicy>, carry_over: CarryOver, tail_policy: TailPolicy) -> Result<()> {
+        ctx.accounts.set_policy(carry_over, tail_policy, &ctx.bumps)
     }
 
     pub fn enable_vesting(ctx: Context<VestingControl>) -> Result<()> {
-        ctx.accounts.enable_vesting()
+        ctx.accounts.enable_vesting(&ctx.bumps)
     }
 
     pub fn disable_vesting(ctx: Context<VestingControl>) -> Result<()> {
-        ctx.accounts.disable_vesting()
+        ctx.accounts.disable_vesting(&ctx.bumps)
     }
 
-    pub fn withdraw_tokens(ctx: Context<WithdrawTokens>, token_amount: u64) -> Result<()> {
-        ctx.accounts.withdraw_remaining_tokens(token_amount)
+    pub fn withdraw_tokens<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawTokens<'info>>, token_amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_remaining_tokens(token_amount, &ctx.bumps, ctx.remaining_accounts)
     }
 }
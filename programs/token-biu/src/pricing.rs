@@ -0,0 +1,19 @@
+//! SOL/USD price from the Pyth feed, shared by purchases and their quotes.
+
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
+use crate::constants::*;
+
+/// SOL/USD price of `price_update`, no older than `MAX_AGE`
+pub fn sol_price_usd(price_update: &PriceUpdateV2) -> Result<f64> {
+
+    let feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
+    let price_data = price_update.get_price_no_older_than(
+        &Clock::get()?,
+        MAX_AGE,
+        &feed_id,
+    )?;
+    let sol_price_usd = (price_data.price as f64) * 10f64.powi(price_data.exponent);
+
+    Ok(sol_price_usd)
+}
@@ -0,0 +1,34 @@
+//! Resizing accounts whose contents grow after they are created.
+
+use anchor_lang::{prelude::*, system_program};
+
+/// Resize `account` to `len` bytes, `payer` topping up its rent exemption
+/// when it grows
+pub fn resize<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    len: usize,
+) -> Result<()> {
+
+    let shortfall = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    account.realloc(len, false)?;
+
+    Ok(())
+}
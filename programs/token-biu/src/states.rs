@@ -1,4 +1,6 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, Discriminator};
+use std::ops::Range;
+use crate::{constants::*, error::ErrorCode};
 
 #[account]
 pub struct SaleConfig {
@@ -11,6 +13,198 @@ pub struct SaleConfig {
     pub wallet_purchase_limit: u64,
     pub bump: u8,
     pub paused: bool,
+    pub lockup_duration: i64,
+    pub mint_on_purchase: bool,
+    pub max_supply: u64,
+    pub tokens_minted: u64,
+    pub state: SaleState,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub unsold_policy: UnsoldPolicy,
+    pub tokens_deposited: u64,
+    pub tokens_released: u64,
+}
+
+impl SaleConfig {
+    /// USD value of `sol_amount` lamports at `sol_price_usd`
+    pub fn usd_value(sol_amount: u64, sol_price_usd: f64) -> f64 {
+
+        sol_amount as f64 / 10_f64.powf(SOL_DECIMALS) * sol_price_usd
+    }
+
+    /// Tokens (in base units) bought by `sol_amount` lamports at `sol_price_usd`
+    pub fn token_amount(&self, sol_amount: u64, sol_price_usd: f64) -> u64 {
+
+        let sol_amount_usd = Self::usd_value(sol_amount, sol_price_usd);
+        let decimals = self.mint_decimals;
+
+        (sol_amount_usd / self.token_price_usd * 10_f64.powf(decimals as f64)) as u64
+    }
+
+    /// State at `now`: an active sale past its end time has ended, even
+    /// before anyone records it
+    pub fn current_state(&self, now: i64) -> SaleState {
+
+        if self.state == SaleState::Active && self.end_time != 0 && now >= self.end_time {
+            SaleState::Ended
+        } else {
+            self.state
+        }
+    }
+
+    /// Record a state reached by time; returns the state it leaves if it
+    /// changed
+    pub fn sync_state(&mut self, now: i64) -> Option<SaleState> {
+
+        let old_state = self.state;
+        self.state = self.current_state(now);
+
+        (self.state != old_state).then_some(old_state)
+    }
+
+    /// Move one step along Draft → Active → Ended → Finalized; returns the
+    /// state it leaves
+    pub fn transition(&mut self, new_state: SaleState) -> Result<SaleState> {
+
+        let old_state = self.state;
+        let allowed = matches!(
+            (old_state, new_state),
+            (SaleState::Draft, SaleState::Active)
+                | (SaleState::Active, SaleState::Ended)
+                | (SaleState::Ended, SaleState::Finalized)
+        );
+        require!(allowed, ErrorCode::InvalidStateTransition);
+
+        self.state = new_state;
+
+        Ok(old_state)
+    }
+
+    /// Check purchases are open at `now`
+    pub fn require_open(&self, now: i64) -> Result<()> {
+
+        match self.current_state(now) {
+            SaleState::Draft => return err!(ErrorCode::SaleNotActive),
+            SaleState::Ended | SaleState::Finalized => return err!(ErrorCode::SaleEnded),
+            SaleState::Active => {}
+        }
+        require!(now >= self.start_time, ErrorCode::SaleNotStarted);
+
+        Ok(())
+    }
+
+    /// Tokens deposited with `fund_vault` that the program hasn't sent or
+    /// burned from the vault since
+    pub fn deposited_balance(&self) -> u64 {
+
+        self.tokens_deposited.saturating_sub(self.tokens_released)
+    }
+
+    /// Record `token_amount` deposited into the vault
+    pub fn deposit(&mut self, token_amount: u64) -> Result<()> {
+
+        self.tokens_deposited = self.tokens_deposited
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Record `token_amount` leaving the vault, sent or burned
+    pub fn release(&mut self, token_amount: u64) -> Result<()> {
+
+        self.tokens_released = self.tokens_released
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Tokens the sale can still hand out: the vault, plus what it may mint
+    /// under the max supply when minting on purchase
+    pub fn token_capacity(&self, vault_balance: u64, mint_supply: u64) -> u64 {
+
+        if !self.mint_on_purchase {
+            return vault_balance;
+        }
+
+        vault_balance.saturating_add(self.max_supply.saturating_sub(mint_supply))
+    }
+
+    /// Part of `token_amount` unsold allocation to burn from the vault. When
+    /// minting on purchase, what the vault doesn't hold was never minted and
+    /// comes off the max supply instead.
+    pub fn vault_burn(&mut self, token_amount: u64, vault_balance: u64) -> u64 {
+
+        if !self.mint_on_purchase {
+            return token_amount;
+        }
+
+        let from_vault = token_amount.min(vault_balance);
+        self.max_supply = self.max_supply.saturating_sub(token_amount - from_vault);
+
+        from_vault
+    }
+}
+
+/// Running totals of a sale's purchases, for dashboards
+#[account]
+pub struct SaleStats {
+    pub sale_config: Pubkey,
+    pub lamports_raised: u64,
+    pub usd_raised: f64,
+    pub tokens_sold: u64,
+    pub unique_buyers: u64,
+    pub purchase_count: u64,
+    pub largest_purchase: u64,
+    pub bump: u8,
+}
+
+impl SaleStats {
+    /// Add a purchase of `token_amount` for `sol_amount` lamports worth
+    /// `usd_value`; `new_buyer` for the buyer's first purchase in the sale
+    pub fn record_purchase(&mut self, sol_amount: u64, usd_value: f64, token_amount: u64, new_buyer: bool) -> Result<()> {
+
+        self.lamports_raised = self.lamports_raised
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.usd_raised += usd_value;
+        self.tokens_sold = self.tokens_sold
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.purchase_count += 1;
+        self.largest_purchase = self.largest_purchase.max(token_amount);
+
+        if new_buyer {
+            self.unique_buyers += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Where the sale is in its lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaleState {
+    /// Being set up; no purchases yet
+    #[default]
+    Draft,
+    /// Open for purchases from `start_time` until `end_time`
+    Active,
+    /// Closed to purchases, by the authority or because `end_time` passed
+    Ended,
+    /// Wound down for good
+    Finalized,
+}
+
+/// What `finalize_sale` does with the tokens left in the vault
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnsoldPolicy {
+    /// Sent to the authority's token account
+    #[default]
+    ReturnToTreasury,
+    /// Burned
+    Burn,
 }
 
 #[account]
@@ -19,16 +213,1105 @@ pub struct WalletPurchase {
     pub total_purchased: u64,
     pub last_purchased_timestamp: i64,
     pub bump: u8,
+    pub sale_config: Pubkey,
+    pub lifetime_purchased: u64,    // Every token bought since the record was created
+    pub locks: Vec<Lock>,           // Purchases still locked, each with its own unlock date
+}
+
+/// Tokens from one purchase the wallet must hold until `unlock_timestamp`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lock {
+    pub amount: u64,
+    pub unlock_timestamp: i64,
+}
+
+/// `WalletPurchase` as the first release stored it, before sales, lifetime
+/// totals and lockups were tracked
+#[derive(AnchorDeserialize)]
+struct LegacyWalletPurchase {
+    wallet: Pubkey,
+    total_purchased: u64,
+    last_purchased_timestamp: i64,
+    bump: u8,
+}
+
+impl From<LegacyWalletPurchase> for WalletPurchase {
+    fn from(legacy: LegacyWalletPurchase) -> Self {
+        WalletPurchase {
+            wallet: legacy.wallet,
+            total_purchased: legacy.total_purchased,
+            last_purchased_timestamp: legacy.last_purchased_timestamp,
+            bump: legacy.bump,
+            sale_config: Pubkey::default(),
+            lifetime_purchased: DEFAULT,
+            locks: Vec::new(),
+        }
+    }
+}
+
+impl WalletPurchase {
+    /// Read `buyer`'s record from `account`, `None` if the buyer has none yet.
+    /// Only this program can create data at the PDA, so a non-empty account
+    /// there is always a record.
+    pub fn load(account: &AccountInfo, buyer: &Pubkey) -> Result<Option<Self>> {
+
+        let (address, _) = Pubkey::find_program_address(&[WALLET_PURCHASE, buyer.as_ref()], &crate::ID);
+        require_keys_eq!(account.key(), address, ErrorCode::InvalidWalletPurchase);
+
+        if account.data_is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::read(&account.try_borrow_data()?)?))
+    }
+
+    /// Account size holding `locks` locks
+    pub const fn space(locks: usize) -> usize {
+        WALLET_PURCHASE_SIZE + locks * LOCK_SIZE
+    }
+
+    /// Whether `data` is a record in the first release's layout, which
+    /// `migrate_wallet_purchase` must rewrite before purchases can update it.
+    /// Records in the current layout are never that size.
+    pub fn is_legacy(data: &[u8]) -> bool {
+        data.len() == LEGACY_WALLET_PURCHASE_SIZE
+    }
+
+    /// Deserialize a record in the current layout or the first release's
+    pub fn read(data: &[u8]) -> Result<Self> {
+
+        if !Self::is_legacy(data) {
+            return Self::try_deserialize(&mut &data[..]);
+        }
+
+        require!(
+            data.starts_with(&Self::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        Ok(LegacyWalletPurchase::deserialize(&mut &data[8..])?.into())
+    }
+
+    /// Tokens bought in the daily purchase window still running at `now`;
+    /// nothing once it has passed
+    pub fn window_purchased(&self, now: i64) -> u64 {
+
+        if now - self.last_purchased_timestamp > SECONDS_IN_A_DAY {
+            DEFAULT
+        } else {
+            self.total_purchased
+        }
+    }
+
+    /// Purchased tokens the wallet must still hold at `now`
+    pub fn locked_at(&self, now: i64) -> u64 {
+
+        self.locks
+            .iter()
+            .filter(|lock| now < lock.unlock_timestamp)
+            .map(|lock| lock.amount)
+            .sum()
+    }
+
+    /// When the daily purchase window ends and `window_purchased` drops to
+    /// nothing, `None` if nothing was bought in it
+    pub fn window_resets_at(&self, now: i64) -> Option<i64> {
+
+        if self.window_purchased(now) == DEFAULT {
+            return None;
+        }
+
+        Some(self.last_purchased_timestamp + SECONDS_IN_A_DAY + 1)
+    }
+
+    /// Whether the record can go: no tokens still locked, and the sale it
+    /// was last used in is over, so it can't be bought in again and a new
+    /// record can't count as another buyer or reset the totals
+    pub fn is_closable(&self, now: i64, sale_over: bool) -> bool {
+
+        self.locked_at(now) == 0 && sale_over
+    }
+
+    /// Lock `amount` more tokens for `duration` seconds from `now`. Earlier
+    /// purchases keep their own unlock dates; locks that have run out are
+    /// dropped, and one ending at the same time is topped up instead.
+    pub fn lock(&mut self, amount: u64, now: i64, duration: i64) -> Result<()> {
+
+        let unlock_timestamp = now
+            .checked_add(duration)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.locks.retain(|lock| now < lock.unlock_timestamp);
+
+        match self.locks.iter_mut().find(|lock| lock.unlock_timestamp == unlock_timestamp) {
+            Some(lock) => {
+                lock.amount = lock.amount
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            None => {
+                require!(self.locks.len() < MAX_LOCKS, ErrorCode::TooManyLocks);
+                self.locks.push(Lock { amount, unlock_timestamp });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Which of the schedule's allowances a transfer draws from
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Allowance {
+    Sale,
+    Treasury,
+}
+
+/// What happens to the sale allocation left unsold when a period ends
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CarryOver {
+    /// Stays available to buyers in the next period
+    #[default]
+    Rollover,
+    /// Moves to the treasury allowance
+    ExpireToTreasury,
+    /// Is burned from the vault
+    Burn,
+}
+
+/// What is available once the final period has been unlocked
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TailPolicy {
+    /// The whole vault is available to both allowances
+    #[default]
+    UnlockVault,
+    /// The vault beyond the sale allowance goes to the treasury
+    TreasuryOnly,
+    /// Nothing beyond the scheduled allocations is unlocked
+    Locked,
+}
+
+/// Periods crossed by one call to `unlock_periods`: indices from
+/// `first_period` up to the new `periods_unlocked`, and the unsold sale
+/// allocation their carry-over moved to the treasury
+#[derive(Debug)]
+pub struct Advance {
+    pub first_period: u16,
+    pub expired: u64,
+}
+
+/// A wallet's purchase allowances right now, returned by `wallet_status`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalletStatus {
+    pub window_purchased: u64,         // Tokens bought in the current daily window
+    pub window_resets_at: Option<i64>, // When the window ends, `None` if nothing was bought in it
+    pub lifetime_purchased: u64,
+    pub wallet_remaining: u64,         // Wallet limit left in the current window
+    pub period_remaining: Option<u64>, // Sale allowance left in the current period, `None` without vesting
+}
+
+/// What `buy_tokens` would do with a given amount right now, returned by
+/// `quote_buy`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BuyQuote {
+    pub token_amount: u64,
+    pub sol_price_usd: f64,
+    pub transfer_fee: u64,          // Token-2022 fee the vault pays on top
+    pub wallet_remaining: u64,      // Wallet limit left in the daily window after the purchase
+    pub period_remaining: Option<u64>, // Sale allowance left after the purchase, `None` without vesting
 }
 
 #[account]
 pub struct MonthlyLimits {
-    pub timestamps: [i64; 14],
-    pub limits: [u64; 14],
-    pub tokens_unlocked: u64,       // Tokens unlocked so far
-    pub tokens_available: u64,
+    pub timestamps: Vec<i64>,
+    pub limits: Vec<u64>,           // Public sale allocation of each period
+    pub treasury_limits: Vec<u64>,  // Treasury allocation of each period
+    pub tokens_unlocked: u64,       // Tokens sold or withdrawn so far
+    pub sale_available: u64,
+    pub tokens_sold: u64,
+    pub treasury_available: u64,
     pub tokens_withdrawn: u64,
-    pub last_checked_index: u8,
+    pub periods_unlocked: u16,      // Periods whose allocations were added
     pub is_vesting_enabled: bool,
+    pub carry_over: CarryOver,
+    pub tail_policy: TailPolicy,
+    pub tokens_expired: u64,        // Unsold sale allocation moved to the treasury
+    pub pending_burn: u64,          // Unsold sale allocation still to be burned
+    pub tokens_burned: u64,
+    pub period: Period,             // Length of the generated schedule's periods
+    pub period_tokens_sold: Vec<u64>, // Tokens bought during each period
+    pub period_sol_raised: Vec<u64>,  // Lamports paid during each period
+}
+
+impl MonthlyLimits {
+    /// Account size needed to hold `periods` periods
+    pub const fn space(periods: usize) -> usize {
+        MONTHLY_LIMITS_SIZE + periods * MONTHLY_LIMITS_PERIOD_SIZE
+    }
+
+    /// Build the generated schedule from a start time and unlock amounts.
+    ///
+    /// Indices before `FIRST_HALF` unlock `period_unlock` for the sale and
+    /// `treasury_unlock` for the treasury at the start of each period. The
+    /// bulk unlock goes to the sale, split in two: `FIRST_HALF` unlocks the
+    /// first half after `period.count()` periods, `SECOND_HALF` the rest half
+    /// as many periods later.
+    ///
+    /// Calendar-monthly schedules must start on the 1st of a month at 00:00
+    /// UTC and unlock on the 1st of every following month.
+    pub fn generate_schedule(
+        start_time: i64,
+        period: Period,
+        period_unlock: u64,
+        treasury_unlock: u64,
+        bulk_unlock: u64,
+    ) -> Result<(Vec<u64>, Vec<u64>, Vec<i64>)> {
+
+        require!(period.is_valid_start(start_time), ErrorCode::InvalidStartTime);
+
+        let period_start = |index: i64| -> Result<i64> {
+            period
+                .start(start_time, index)
+                .ok_or(ErrorCode::MathOverflow.into())
+        };
+
+        let mut limits = vec![DEFAULT; GENERATED_PERIODS];
+        let mut treasury_limits = vec![DEFAULT; GENERATED_PERIODS];
+        let mut timestamps = vec![DEFAULT as i64; GENERATED_PERIODS];
+
+        for i in 0..FIRST_HALF {
+            limits[i] = period_unlock;
+            treasury_limits[i] = treasury_unlock;
+            timestamps[i] = period_start(i as i64)?;
+        }
+
+        let first_half = bulk_unlock / 2;
+        limits[FIRST_HALF] = first_half;
+        limits[SECOND_HALF] = bulk_unlock - first_half;
+        timestamps[FIRST_HALF] = period_start(period.count())?;
+        timestamps[SECOND_HALF] = period_start(period.count() + period.count() / 2)?;
+
+        Ok((limits, treasury_limits, timestamps))
+    }
+
+    /// Check a schedule can be stored: one sale and one treasury limit per
+    /// timestamp, at least one period and no more than `MAX_PERIODS`
+    pub fn validate_length(limits: &[u64], treasury_limits: &[u64], timestamps: &[i64]) -> Result<()> {
+
+        require!(
+            limits.len() == timestamps.len() && treasury_limits.len() == timestamps.len(),
+            ErrorCode::ScheduleLengthMismatch
+        );
+        require!(!limits.is_empty(), ErrorCode::EmptySchedule);
+        require!(limits.len() <= MAX_PERIODS, ErrorCode::ScheduleTooLong);
+
+        Ok(())
+    }
+
+    /// Check a whole schedule: valid length and strictly increasing timestamps
+    pub fn validate_schedule(limits: &[u64], treasury_limits: &[u64], timestamps: &[i64]) -> Result<()> {
+
+        Self::validate_length(limits, treasury_limits, timestamps)?;
+
+        require!(
+            timestamps.windows(2).all(|pair| pair[0] < pair[1]),
+            ErrorCode::InvalidScheduleOrder
+        );
+
+        Ok(())
+    }
+
+    /// Whether the first period has been reached
+    pub fn has_started(&self, current_timestamp: i64) -> bool {
+
+        self.periods_unlocked > 0
+            || self.timestamps.first().is_some_and(|start| current_timestamp >= *start)
+    }
+
+    /// Whether every period has been unlocked
+    pub fn is_complete(&self) -> bool {
+        self.periods_unlocked as usize >= self.timestamps.len()
+    }
+
+    /// Tokens the schedule still needs from the vault: what both allowances
+    /// hold now, what is waiting to be burned, plus the allocations of every
+    /// period not unlocked yet
+    pub fn outstanding_unlocks(&self) -> Result<u64> {
+
+        let first_pending = self.periods_unlocked as usize;
+
+        self.limits
+            .iter()
+            .skip(first_pending)
+            .chain(self.treasury_limits.iter().skip(first_pending))
+            .try_fold(self.sale_available, |total, limit| total.checked_add(*limit))
+            .and_then(|total| total.checked_add(self.treasury_available))
+            .and_then(|total| total.checked_add(self.pending_burn))
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// The vault must hold everything the schedule can still unlock, going
+    /// by the tokens deposited with `fund_vault`
+    pub fn require_funded(&self, vault_balance: u64) -> Result<()> {
+
+        require!(
+            self.outstanding_unlocks()? <= vault_balance,
+            ErrorCode::ScheduleExceedsVault
+        );
+
+        Ok(())
+    }
+
+    /// Replace the schedule, enable vesting and restart the accounting
+    pub fn set_schedule(&mut self, limits: Vec<u64>, treasury_limits: Vec<u64>, timestamps: Vec<i64>) {
+
+        self.limits = limits;
+        self.treasury_limits = treasury_limits;
+        self.timestamps = timestamps;
+        self.is_vesting_enabled = true;
+        self.tokens_unlocked = DEFAULT;
+        self.sale_available = DEFAULT;
+        self.tokens_sold = DEFAULT;
+        self.treasury_available = DEFAULT;
+        self.tokens_withdrawn = DEFAULT;
+        self.periods_unlocked = DEFAULT as u16;
+        self.tokens_expired = DEFAULT;
+        self.tokens_burned = DEFAULT;
+        self.period_tokens_sold = vec![DEFAULT; self.timestamps.len()];
+        self.period_sol_raised = vec![DEFAULT; self.timestamps.len()];
+    }
+
+    /// Replace every period from `from_index` on, keeping the accounting.
+    ///
+    /// Only periods that have not been reached can change, and the new ones
+    /// must all start in the future.
+    pub fn amend_schedule(
+        &mut self,
+        from_index: usize,
+        limits: &[u64],
+        treasury_limits: &[u64],
+        timestamps: &[i64],
+        current_timestamp: i64,
+    ) -> Result<()> {
+
+        require!(
+            limits.len() == timestamps.len() && treasury_limits.len() == timestamps.len(),
+            ErrorCode::ScheduleLengthMismatch
+        );
+        require!(from_index <= self.timestamps.len(), ErrorCode::InvalidAmendIndex);
+
+        let unlocked = from_index < self.periods_unlocked as usize;
+        let replaced_reached = self.timestamps[from_index..]
+            .iter()
+            .any(|timestamp| *timestamp <= current_timestamp);
+        let new_reached = timestamps
+            .iter()
+            .any(|timestamp| *timestamp <= current_timestamp);
+
+        require!(
+            !unlocked && !replaced_reached && !new_reached,
+            ErrorCode::PeriodAlreadyReached
+        );
+
+        self.limits.truncate(from_index);
+        self.treasury_limits.truncate(from_index);
+        self.timestamps.truncate(from_index);
+        self.limits.extend_from_slice(limits);
+        self.treasury_limits.extend_from_slice(treasury_limits);
+        self.timestamps.extend_from_slice(timestamps);
+        self.period_tokens_sold.resize(self.timestamps.len(), DEFAULT);
+        self.period_sol_raised.resize(self.timestamps.len(), DEFAULT);
+
+        Self::validate_schedule(&self.limits, &self.treasury_limits, &self.timestamps)
+    }
+
+    /// Index of the period `current_timestamp` falls in, `None` before the
+    /// schedule starts
+    pub fn period_at(&self, current_timestamp: i64) -> Option<usize> {
+
+        self.timestamps
+            .partition_point(|timestamp| *timestamp <= current_timestamp)
+            .checked_sub(1)
+    }
+
+    /// Periods that started but weren't closed out by the next unlock: the
+    /// one in progress at `current_timestamp` and any the schedule hasn't
+    /// advanced past yet
+    pub fn unclosed_periods(&self, current_timestamp: i64) -> Range<usize> {
+
+        let first = (self.periods_unlocked as usize).saturating_sub(1);
+        let end = self.period_at(current_timestamp).map_or(DEFAULT as usize, |index| index + 1);
+
+        first..end.max(first)
+    }
+
+    /// Add a purchase to the sales of the period it was made in, whether or
+    /// not vesting is enabled; purchases before the schedule starts aren't
+    /// counted
+    pub fn record_period_sale(&mut self, token_amount: u64, sol_amount: u64, current_timestamp: i64) -> Result<()> {
+
+        let Some(index) = self.period_at(current_timestamp) else {
+            return Ok(());
+        };
+
+        self.period_tokens_sold[index] = self.period_tokens_sold[index]
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.period_sol_raised[index] = self.period_sol_raised[index]
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Sale allowance a purchase at `current_timestamp` could draw from,
+    /// unlocking periods on a copy exactly as `draw` would
+    pub fn sale_allowance_at(&self, current_timestamp: i64, vault_balance: u64) -> Result<u64> {
+
+        if !self.has_started(current_timestamp) {
+            return Ok(DEFAULT);
+        }
+
+        let mut monthly_limits = self.clone();
+        monthly_limits.unlock_periods(current_timestamp)?;
+
+        if monthly_limits.is_complete() {
+            monthly_limits.apply_tail(vault_balance);
+        }
+
+        Ok(monthly_limits.sale_available)
+    }
+
+    /// Add the sale and treasury allocations of every period reached by
+    /// `current_timestamp` that hasn't been unlocked yet.
+    ///
+    /// Each time a period ends, the sale allocation still unsold is handled
+    /// by the schedule's `carry_over` policy before the next one is added.
+    pub fn unlock_periods(&mut self, current_timestamp: i64) -> Result<Advance> {
+
+        let mut advance = Advance {
+            first_period: self.periods_unlocked,
+            expired: DEFAULT,
+        };
+
+        while !self.is_complete()
+            && current_timestamp >= self.timestamps[self.periods_unlocked as usize]
+        {
+            let index = self.periods_unlocked as usize;
+
+            if index > 0 {
+                self.carry_over_unsold(&mut advance)?;
+            }
+
+            self.sale_available = self.sale_available
+                .checked_add(self.limits[index])
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.treasury_available = self.treasury_available
+                .checked_add(self.treasury_limits[index])
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.periods_unlocked += 1;
+        }
+
+        Ok(advance)
+    }
+
+    /// Apply the carry-over policy to the sale allowance of the period that
+    /// just ended
+    fn carry_over_unsold(&mut self, advance: &mut Advance) -> Result<()> {
+
+        let unsold = self.sale_available;
+
+        match self.carry_over {
+            CarryOver::Rollover => return Ok(()),
+            CarryOver::ExpireToTreasury => {
+                self.treasury_available = self.treasury_available
+                    .checked_add(unsold)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                self.tokens_expired = self.tokens_expired
+                    .checked_add(unsold)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                advance.expired += unsold;
+            }
+            CarryOver::Burn => {
+                self.pending_burn = self.pending_burn
+                    .checked_add(unsold)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+
+        self.sale_available = DEFAULT;
+
+        Ok(())
+    }
+
+    /// Once the final period is unlocked, size the allowances from what is
+    /// left in the vault according to the tail policy
+    fn apply_tail(&mut self, vault_balance: u64) {
+
+        let unlocked = vault_balance.saturating_sub(self.pending_burn);
+
+        match self.tail_policy {
+            TailPolicy::UnlockVault => {
+                self.sale_available = unlocked;
+                self.treasury_available = unlocked;
+            }
+            TailPolicy::TreasuryOnly => {
+                self.treasury_available = unlocked.saturating_sub(self.sale_available);
+            }
+            TailPolicy::Locked => {}
+        }
+    }
+
+    /// Record tokens burned from the vault out of `pending_burn`
+    pub fn record_burn(&mut self, token_amount: u64) -> Result<()> {
+
+        self.pending_burn = self.pending_burn
+            .checked_sub(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.tokens_burned = self.tokens_burned
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Unlock every period reached so far and take `token_amount` out of
+    /// `allowance`.
+    ///
+    /// Once the last period is unlocked, what is left of `vault_balance` is
+    /// shared out according to the tail policy.
+    pub fn draw(
+        &mut self,
+        allowance: Allowance,
+        token_amount: u64,
+        current_timestamp: i64,
+        vault_balance: u64,
+    ) -> Result<Advance> {
+
+        require!(self.has_started(current_timestamp), ErrorCode::SaleNotStarted);
+
+        let advance = self.unlock_periods(current_timestamp)?;
+
+        if self.is_complete() {
+            self.apply_tail(vault_balance);
+        }
+
+        let (available, drawn, exceeded) = match allowance {
+            Allowance::Sale => (
+                &mut self.sale_available,
+                &mut self.tokens_sold,
+                ErrorCode::MonthlyLimitExceeded,
+            ),
+            Allowance::Treasury => (
+                &mut self.treasury_available,
+                &mut self.tokens_withdrawn,
+                ErrorCode::TreasuryLimitExceeded,
+            ),
+        };
+
+        if token_amount > *available {
+            return Err(exceeded.into());
+        }
+
+        *available -= token_amount;
+        *drawn = drawn.checked_add(token_amount).ok_or(ErrorCode::MathOverflow)?;
+        self.tokens_unlocked = self.tokens_unlocked
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(advance)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const START: i64 = 1_704_067_200; // 2024-01-01 00:00 UTC
+
+    /// A schedule of `limits.len()` daily periods from `START`
+    fn schedule(limits: &[u64], treasury_limits: &[u64]) -> MonthlyLimits {
+        let mut monthly_limits = MonthlyLimits {
+            timestamps: Vec::new(),
+            limits: Vec::new(),
+            treasury_limits: Vec::new(),
+            tokens_unlocked: DEFAULT,
+            sale_available: DEFAULT,
+            tokens_sold: DEFAULT,
+            treasury_available: DEFAULT,
+            tokens_withdrawn: DEFAULT,
+            periods_unlocked: DEFAULT as u16,
+            is_vesting_enabled: false,
+            carry_over: CarryOver::default(),
+            tail_policy: TailPolicy::default(),
+            tokens_expired: DEFAULT,
+            pending_burn: DEFAULT,
+            tokens_burned: DEFAULT,
+            period: Period::Daily,
+            period_tokens_sold: Vec::new(),
+            period_sol_raised: Vec::new(),
+        };
+        let timestamps = (0..limits.len() as i64).map(|i| START + i * DAY).collect();
+        monthly_limits.set_schedule(limits.to_vec(), treasury_limits.to_vec(), timestamps);
+
+        monthly_limits
+    }
+
+    #[test]
+    fn validate_schedule_rejects_bad_schedules() {
+        assert!(MonthlyLimits::validate_schedule(&[1, 2], &[0, 0], &[START, START + DAY]).is_ok());
+
+        assert_eq!(
+            MonthlyLimits::validate_schedule(&[1, 2], &[0], &[START, START + DAY]).unwrap_err(),
+            ErrorCode::ScheduleLengthMismatch.into()
+        );
+        assert_eq!(
+            MonthlyLimits::validate_schedule(&[], &[], &[]).unwrap_err(),
+            ErrorCode::EmptySchedule.into()
+        );
+        assert_eq!(
+            MonthlyLimits::validate_schedule(&[1, 2], &[0, 0], &[START, START]).unwrap_err(),
+            ErrorCode::InvalidScheduleOrder.into()
+        );
+
+        let too_long = vec![1; MAX_PERIODS + 1];
+        let timestamps: Vec<i64> = (0..too_long.len() as i64).map(|i| START + i).collect();
+        assert_eq!(
+            MonthlyLimits::validate_schedule(&too_long, &too_long, &timestamps).unwrap_err(),
+            ErrorCode::ScheduleTooLong.into()
+        );
+    }
+
+    #[test]
+    fn generated_schedules_split_the_bulk_unlock_in_two() {
+        let (limits, treasury_limits, timestamps) =
+            MonthlyLimits::generate_schedule(START, Period::Daily, 10, 2, 101).unwrap();
+
+        assert_eq!(limits.len(), GENERATED_PERIODS);
+        assert_eq!(limits[..FIRST_HALF], [10; FIRST_HALF]);
+        assert_eq!(treasury_limits[..FIRST_HALF], [2; FIRST_HALF]);
+        assert_eq!(timestamps[..FIRST_HALF], (0..FIRST_HALF as i64).map(|i| START + i * DAY).collect::<Vec<_>>());
+
+        assert_eq!((limits[FIRST_HALF], limits[SECOND_HALF]), (50, 51));
+        assert_eq!((treasury_limits[FIRST_HALF], treasury_limits[SECOND_HALF]), (0, 0));
+        assert_eq!((timestamps[FIRST_HALF], timestamps[SECOND_HALF]), (START + 12 * DAY, START + 18 * DAY));
+        assert!(MonthlyLimits::validate_schedule(&limits, &treasury_limits, &timestamps).is_ok());
+    }
+
+    #[test]
+    fn calendar_monthly_schedules_start_on_the_first_of_a_month() {
+        let (_, _, timestamps) =
+            MonthlyLimits::generate_schedule(START, Period::CalendarMonthly, 10, 0, 100).unwrap();
+        assert_eq!(timestamps[1], START + 31 * DAY); // 2024-02-01
+        assert_eq!(timestamps[2], START + 60 * DAY); // 2024-03-01, a leap year
+
+        for start_time in [START + DAY, START + 1] {
+            assert_eq!(
+                MonthlyLimits::generate_schedule(start_time, Period::CalendarMonthly, 10, 0, 100).unwrap_err(),
+                ErrorCode::InvalidStartTime.into()
+            );
+        }
+    }
+
+    #[test]
+    fn amend_at_the_end_appends_periods() {
+        let mut monthly_limits = schedule(&[10, 20], &[1, 2]);
+        let end = START + 3 * DAY;
+
+        monthly_limits
+            .amend_schedule(2, &[30], &[3], &[end], START - 1)
+            .unwrap();
+
+        assert_eq!(monthly_limits.limits, [10, 20, 30]);
+        assert_eq!(monthly_limits.treasury_limits, [1, 2, 3]);
+        assert_eq!(monthly_limits.timestamps, [START, START + DAY, end]);
+        assert_eq!(monthly_limits.period_tokens_sold.len(), 3);
+        assert_eq!(monthly_limits.period_sol_raised.len(), 3);
+    }
+
+    #[test]
+    fn amend_past_the_end_is_rejected() {
+        let mut monthly_limits = schedule(&[10, 20], &[1, 2]);
+
+        assert_eq!(
+            monthly_limits
+                .amend_schedule(3, &[30], &[3], &[START + 3 * DAY], START - 1)
+                .unwrap_err(),
+            ErrorCode::InvalidAmendIndex.into()
+        );
+    }
+
+    #[test]
+    fn amend_of_a_reached_period_is_rejected() {
+        let mut monthly_limits = schedule(&[10, 20, 30], &[1, 2, 3]);
+        let now = START + DAY;
+
+        // Reached by the clock, though not unlocked yet
+        assert_eq!(
+            monthly_limits
+                .amend_schedule(1, &[25], &[2], &[START + 2 * DAY], now)
+                .unwrap_err(),
+            ErrorCode::PeriodAlreadyReached.into()
+        );
+
+        // New periods must start in the future too
+        assert_eq!(
+            monthly_limits
+                .amend_schedule(2, &[35], &[3], &[now], now)
+                .unwrap_err(),
+            ErrorCode::PeriodAlreadyReached.into()
+        );
+
+        // Unlocked periods can't change even if their timestamp moved
+        monthly_limits.unlock_periods(now).unwrap();
+        assert_eq!(
+            monthly_limits
+                .amend_schedule(1, &[25], &[2], &[START + 2 * DAY], START)
+                .unwrap_err(),
+            ErrorCode::PeriodAlreadyReached.into()
+        );
+
+        assert_eq!(monthly_limits.limits, [10, 20, 30]);
+    }
+
+    #[test]
+    fn amend_with_an_empty_list_drops_later_periods() {
+        let mut monthly_limits = schedule(&[10, 20, 30], &[1, 2, 3]);
+
+        monthly_limits.amend_schedule(1, &[], &[], &[], START - 1).unwrap();
+
+        assert_eq!(monthly_limits.limits, [10]);
+        assert_eq!(monthly_limits.timestamps, [START]);
+        assert_eq!(monthly_limits.period_tokens_sold.len(), 1);
+
+        // but can't leave the schedule empty
+        assert_eq!(
+            monthly_limits.amend_schedule(0, &[], &[], &[], START - 1).unwrap_err(),
+            ErrorCode::EmptySchedule.into()
+        );
+    }
+
+    #[test]
+    fn require_funded_rejects_schedules_beyond_deposits() {
+        let mut monthly_limits = schedule(&[10, 20], &[1, 2]);
+
+        assert_eq!(monthly_limits.outstanding_unlocks().unwrap(), 33);
+        assert!(monthly_limits.require_funded(33).is_ok());
+        assert_eq!(
+            monthly_limits.require_funded(32).unwrap_err(),
+            ErrorCode::ScheduleExceedsVault.into()
+        );
+
+        // Unlocked allocations still count until they are drawn
+        monthly_limits.draw(Allowance::Sale, 4, START, 33).unwrap();
+        assert_eq!(monthly_limits.outstanding_unlocks().unwrap(), 29);
+        assert!(monthly_limits.require_funded(29).is_ok());
+        assert!(monthly_limits.require_funded(28).is_err());
+    }
+
+    /// (sale available, treasury available, tokens expired, pending burn)
+    fn allowances(monthly_limits: &MonthlyLimits) -> (u64, u64, u64, u64) {
+        (
+            monthly_limits.sale_available,
+            monthly_limits.treasury_available,
+            monthly_limits.tokens_expired,
+            monthly_limits.pending_burn,
+        )
+    }
+
+    #[test]
+    fn carry_over_and_tail_policies_across_several_periods() {
+        use CarryOver::*;
+        use TailPolicy::*;
+
+        const VAULT: u64 = 100;
+
+        // Sale 10/20/30 and treasury 1/2/3 a day. 4 tokens are sold on the
+        // first day, then one call crosses the last two periods, handing
+        // the 6 left over and then the 20 of the second day to the
+        // carry-over policy, and completes the schedule.
+        let cases = [
+            (Rollover, UnlockVault, (100, 100, 0, 0)),
+            (Rollover, TreasuryOnly, (56, 44, 0, 0)),
+            (Rollover, Locked, (56, 6, 0, 0)),
+            (ExpireToTreasury, UnlockVault, (100, 100, 26, 0)),
+            (ExpireToTreasury, TreasuryOnly, (30, 70, 26, 0)),
+            (ExpireToTreasury, Locked, (30, 32, 26, 0)),
+            (Burn, UnlockVault, (74, 74, 0, 26)),
+            (Burn, TreasuryOnly, (30, 44, 0, 26)),
+            (Burn, Locked, (30, 6, 0, 26)),
+        ];
+
+        for (carry_over, tail_policy, expected) in cases {
+            let mut monthly_limits = schedule(&[10, 20, 30], &[1, 2, 3]);
+            monthly_limits.carry_over = carry_over;
+            monthly_limits.tail_policy = tail_policy;
+
+            monthly_limits.draw(Allowance::Sale, 4, START, VAULT).unwrap();
+            assert_eq!(allowances(&monthly_limits), (6, 1, 0, 0), "{carry_over:?} {tail_policy:?}");
+
+            let advance = monthly_limits
+                .draw(Allowance::Sale, 0, START + 2 * DAY, VAULT)
+                .unwrap();
+
+            assert_eq!(advance.first_period, 1);
+            assert_eq!(advance.expired, expected.2);
+            assert_eq!(monthly_limits.periods_unlocked, 3);
+            assert_eq!(allowances(&monthly_limits), expected, "{carry_over:?} {tail_policy:?}");
+        }
+    }
+
+    #[test]
+    fn tail_policy_waits_for_the_final_period() {
+        for tail_policy in [TailPolicy::UnlockVault, TailPolicy::TreasuryOnly, TailPolicy::Locked] {
+            let mut monthly_limits = schedule(&[10, 20, 30], &[1, 2, 3]);
+            monthly_limits.tail_policy = tail_policy;
+
+            monthly_limits.draw(Allowance::Treasury, 1, START + DAY, 1_000).unwrap();
+
+            assert!(!monthly_limits.is_complete());
+            assert_eq!(allowances(&monthly_limits), (30, 2, 0, 0), "{tail_policy:?}");
+        }
+    }
+
+    #[test]
+    fn burned_allocation_is_recorded_once_burned() {
+        let mut monthly_limits = schedule(&[10, 20], &[0, 0]);
+        monthly_limits.carry_over = CarryOver::Burn;
+
+        monthly_limits.unlock_periods(START + DAY).unwrap();
+        assert_eq!(monthly_limits.pending_burn, 10);
+        assert_eq!(monthly_limits.outstanding_unlocks().unwrap(), 30);
+
+        monthly_limits.record_burn(10).unwrap();
+        assert_eq!((monthly_limits.pending_burn, monthly_limits.tokens_burned), (0, 10));
+        assert!(monthly_limits.record_burn(1).is_err());
+    }
+
+    #[test]
+    fn draws_beyond_the_allowance_are_rejected() {
+        let mut monthly_limits = schedule(&[10, 20], &[1, 2]);
+
+        assert_eq!(
+            monthly_limits.draw(Allowance::Sale, 1, START - 1, 100).unwrap_err(),
+            ErrorCode::SaleNotStarted.into()
+        );
+        assert_eq!(
+            monthly_limits.draw(Allowance::Sale, 11, START, 100).unwrap_err(),
+            ErrorCode::MonthlyLimitExceeded.into()
+        );
+        assert_eq!(
+            monthly_limits.draw(Allowance::Treasury, 2, START, 100).unwrap_err(),
+            ErrorCode::TreasuryLimitExceeded.into()
+        );
+
+        monthly_limits.draw(Allowance::Sale, 10, START, 100).unwrap();
+        monthly_limits.draw(Allowance::Treasury, 1, START, 100).unwrap();
+        assert_eq!(monthly_limits.tokens_unlocked, 11);
+    }
+
+    fn wallet_purchase() -> WalletPurchase {
+        WalletPurchase {
+            wallet: Pubkey::new_unique(),
+            total_purchased: 0,
+            last_purchased_timestamp: 0,
+            bump: 0,
+            sale_config: Pubkey::new_unique(),
+            lifetime_purchased: 0,
+            locks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn unclosed_periods_run_from_the_last_unlocked_to_the_current_one() {
+        let mut monthly_limits = schedule(&[10, 10, 10, 10], &[0, 0, 0, 0]);
+
+        assert_eq!(monthly_limits.unclosed_periods(START - 1), 0..0);
+        assert_eq!(monthly_limits.unclosed_periods(START + 2 * DAY), 0..3);
+
+        monthly_limits.periods_unlocked = 2;
+        assert_eq!(monthly_limits.unclosed_periods(START + DAY), 1..2);
+        assert_eq!(monthly_limits.unclosed_periods(START + 10 * DAY), 1..4);
+    }
+
+    #[test]
+    fn each_purchase_unlocks_on_its_own_date() {
+        let mut purchase = wallet_purchase();
+
+        purchase.lock(100, START, 10 * DAY).unwrap();
+        purchase.lock(50, START + 5 * DAY, 10 * DAY).unwrap();
+
+        assert_eq!(purchase.locked_at(START + 5 * DAY), 150);
+        assert_eq!(purchase.locked_at(START + 10 * DAY - 1), 150);
+        assert_eq!(purchase.locked_at(START + 10 * DAY), 50);
+        assert_eq!(purchase.locked_at(START + 15 * DAY), 0);
+    }
+
+    #[test]
+    fn records_close_only_once_unlocked_and_their_sale_is_over() {
+        let mut purchase = wallet_purchase();
+        purchase.lock(100, START, DAY).unwrap();
+
+        assert!(!purchase.is_closable(START + 2 * DAY, false));
+        assert!(!purchase.is_closable(START, true));
+        assert!(purchase.is_closable(START + DAY, true));
+    }
+
+    #[test]
+    fn expired_locks_are_dropped_and_simultaneous_ones_shared() {
+        let mut purchase = wallet_purchase();
+
+        purchase.lock(100, START, DAY).unwrap();
+        purchase.lock(10, START + DAY, DAY).unwrap();
+        purchase.lock(5, START + DAY, DAY).unwrap();
+
+        assert_eq!(purchase.locks, [Lock { amount: 15, unlock_timestamp: START + 2 * DAY }]);
+    }
+
+    #[test]
+    fn locks_are_capped_until_one_runs_out() {
+        let mut purchase = wallet_purchase();
+
+        for index in 0..MAX_LOCKS as i64 {
+            purchase.lock(1, START + index, DAY).unwrap();
+        }
+        assert_eq!(
+            purchase.lock(1, START + MAX_LOCKS as i64, DAY).unwrap_err(),
+            ErrorCode::TooManyLocks.into()
+        );
+
+        purchase.lock(1, START + DAY, DAY).unwrap();
+        assert_eq!(purchase.locks.len(), MAX_LOCKS);
+        assert_eq!(purchase.locked_at(START + DAY), MAX_LOCKS as u64);
+    }
+
+    #[test]
+    fn reads_first_release_wallet_purchases() {
+        let wallet = Pubkey::new_unique();
+
+        let mut data = WalletPurchase::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(wallet.as_ref());
+        data.extend_from_slice(&40u64.to_le_bytes());
+        data.extend_from_slice(&START.to_le_bytes());
+        data.push(254);
+        assert_eq!(data.len(), LEGACY_WALLET_PURCHASE_SIZE);
+        assert!(WalletPurchase::is_legacy(&data));
+
+        let purchase = WalletPurchase::read(&data).unwrap();
+        assert_eq!(purchase.wallet, wallet);
+        assert_eq!((purchase.total_purchased, purchase.last_purchased_timestamp), (40, START));
+        assert_eq!(purchase.bump, 254);
+        assert_eq!((purchase.sale_config, purchase.lifetime_purchased), (Pubkey::default(), 0));
+        assert!(purchase.locks.is_empty());
+
+        data[0] ^= 1;
+        assert!(WalletPurchase::read(&data).is_err());
+    }
+
+    #[test]
+    fn current_wallet_purchases_are_never_legacy_sized() {
+        for locks in 0..=MAX_LOCKS {
+            let mut purchase = wallet_purchase();
+            purchase.locks = vec![Lock { amount: 1, unlock_timestamp: START }; locks];
+
+            let mut data = Vec::new();
+            purchase.try_serialize(&mut data).unwrap();
+
+            assert_eq!(data.len(), WalletPurchase::space(locks));
+            assert!(!WalletPurchase::is_legacy(&data));
+            assert_eq!(WalletPurchase::read(&data).unwrap().locks.len(), locks);
+        }
+    }
+
+    /// A sale in `state`, open from `START` until a day later
+    fn sale_config(state: SaleState) -> SaleConfig {
+        SaleConfig {
+            authority: Pubkey::new_unique(),
+            sale_authority: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            token_price_usd: 0.005,
+            mint_decimals: 6,
+            wallet_purchase_limit: 100,
+            bump: 0,
+            paused: false,
+            lockup_duration: 0,
+            mint_on_purchase: false,
+            max_supply: 0,
+            tokens_minted: 0,
+            state,
+            start_time: START,
+            end_time: START + DAY,
+            unsold_policy: UnsoldPolicy::default(),
+            tokens_deposited: 0,
+            tokens_released: 0,
+        }
+    }
+
+    #[test]
+    fn sales_only_move_forward_one_state_at_a_time() {
+        let mut sale_config = sale_config(SaleState::Draft);
+
+        assert_eq!(sale_config.transition(SaleState::Active).unwrap(), SaleState::Draft);
+        assert_eq!(sale_config.transition(SaleState::Ended).unwrap(), SaleState::Active);
+        assert_eq!(sale_config.transition(SaleState::Finalized).unwrap(), SaleState::Ended);
+        assert_eq!(sale_config.state, SaleState::Finalized);
+
+        let rejected = [
+            (SaleState::Draft, SaleState::Ended),
+            (SaleState::Draft, SaleState::Finalized),
+            (SaleState::Active, SaleState::Draft),
+            (SaleState::Active, SaleState::Finalized),
+            (SaleState::Active, SaleState::Active),
+            (SaleState::Ended, SaleState::Active),
+            (SaleState::Finalized, SaleState::Draft),
+            (SaleState::Finalized, SaleState::Ended),
+        ];
+        for (from, to) in rejected {
+            let mut sale_config = self::sale_config(from);
+            assert_eq!(
+                sale_config.transition(to).unwrap_err(),
+                ErrorCode::InvalidStateTransition.into()
+            );
+            assert_eq!(sale_config.state, from);
+        }
+    }
+
+    #[test]
+    fn active_sales_end_at_their_end_time() {
+        let mut sale_config = sale_config(SaleState::Active);
+
+        assert_eq!(sale_config.current_state(START + DAY - 1), SaleState::Active);
+        assert_eq!(sale_config.current_state(START + DAY), SaleState::Ended);
+        assert_eq!(sale_config.sync_state(START + DAY - 1), None);
+        assert_eq!(sale_config.sync_state(START + DAY), Some(SaleState::Active));
+        assert_eq!(sale_config.state, SaleState::Ended);
+        assert_eq!(sale_config.sync_state(START + 2 * DAY), None);
+
+        let mut open_ended = self::sale_config(SaleState::Active);
+        open_ended.end_time = 0;
+        assert_eq!(open_ended.sync_state(i64::MAX), None);
+
+        let draft = self::sale_config(SaleState::Draft);
+        assert_eq!(draft.current_state(START + DAY), SaleState::Draft);
+    }
+
+    #[test]
+    fn purchases_are_open_from_start_to_end_time_of_an_active_sale() {
+        let sale_config = sale_config(SaleState::Active);
+
+        assert_eq!(sale_config.require_open(START - 1).unwrap_err(), ErrorCode::SaleNotStarted.into());
+        assert!(sale_config.require_open(START).is_ok());
+        assert!(sale_config.require_open(START + DAY - 1).is_ok());
+        assert_eq!(sale_config.require_open(START + DAY).unwrap_err(), ErrorCode::SaleEnded.into());
+
+        let closed = [
+            (SaleState::Draft, ErrorCode::SaleNotActive),
+            (SaleState::Ended, ErrorCode::SaleEnded),
+            (SaleState::Finalized, ErrorCode::SaleEnded),
+        ];
+        for (state, error) in closed {
+            assert_eq!(self::sale_config(state).require_open(START).unwrap_err(), error.into());
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Token CPIs out of a sale's vault, signed by the sale's `SALE_AUTHORITY` PDA.
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022,
+    token_interface::{self, Mint, TokenAccount, TokenInterface},
+};
+use crate::{constants::*, events::*, states::{MonthlyLimits, SaleConfig}};
+
+/// The vault token account and the accounts its CPIs need
+pub struct Vault<'info> {
+    pub token_program: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub sale_config: Pubkey,
+    pub authority_bump: u8,
+    pub decimals: u8,
+}
+
+impl<'info> Vault<'info> {
+    /// The vault of `sale_config`: `token_account` of `mint`, owned by the
+    /// sale authority PDA `authority`
+    pub fn new(
+        token_program: &Interface<'info, TokenInterface>,
+        mint: &InterfaceAccount<'info, Mint>,
+        token_account: &InterfaceAccount<'info, TokenAccount>,
+        authority: &SystemAccount<'info>,
+        sale_config: &Account<'info, SaleConfig>,
+    ) -> Self {
+        Vault {
+            token_program: token_program.to_account_info(),
+            mint: mint.to_account_info(),
+            token_account: token_account.to_account_info(),
+            authority: authority.to_account_info(),
+            sale_config: sale_config.key(),
+            authority_bump: sale_config.bump,
+            decimals: mint.decimals,
+        }
+    }
+
+    /// Send `transfer_amount` from the vault to the token account `to`
+    pub fn send(
+        &self,
+        to: AccountInfo<'info>,
+        transfer_amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+
+        self.transfer_checked(
+            self.token_account.clone(),
+            to,
+            self.authority.clone(),
+            transfer_amount,
+            remaining_accounts,
+            &[&[SALE_AUTHORITY, self.sale_config.as_ref(), &[self.authority_bump]]],
+        )
+    }
+
+    /// Move `transfer_amount` into the vault from the token account `from`,
+    /// which `from_authority` signed for
+    pub fn deposit(
+        &self,
+        from: AccountInfo<'info>,
+        from_authority: AccountInfo<'info>,
+        transfer_amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+
+        self.transfer_checked(
+            from,
+            self.token_account.clone(),
+            from_authority,
+            transfer_amount,
+            remaining_accounts,
+            &[],
+        )
+    }
+
+    /// `transfer_checked` of the vault's mint, forwarding the remaining
+    /// accounts so mints with a transfer hook get the extra accounts their
+    /// hook needs
+    fn transfer_checked(
+        &self,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+        authority: AccountInfo<'info>,
+        transfer_amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+
+        spl_token_2022::onchain::invoke_transfer_checked(
+            self.token_program.key,
+            from,
+            self.mint.clone(),
+            to,
+            authority,
+            remaining_accounts,
+            transfer_amount,
+            self.decimals,
+            signer_seeds,
+        )?;
+
+        Ok(())
+    }
+
+    /// Burn `token_amount` from the vault
+    pub fn burn(&self, token_amount: u64) -> Result<()> {
+
+        let authority_seeds: &[&[u8]] = &[
+            SALE_AUTHORITY,
+            self.sale_config.as_ref(),
+            &[self.authority_bump],
+        ];
+
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                token_interface::Burn {
+                    mint: self.mint.clone(),
+                    from: self.token_account.clone(),
+                    authority: self.authority.clone(),
+                },
+                &[authority_seeds]
+            ),
+            token_amount
+        )
+    }
+
+    /// Close the empty vault, sending its rent to `destination`
+    pub fn close(&self, destination: AccountInfo<'info>) -> Result<()> {
+
+        let authority_seeds: &[&[u8]] = &[
+            SALE_AUTHORITY,
+            self.sale_config.as_ref(),
+            &[self.authority_bump],
+        ];
+
+        token_interface::close_account(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                token_interface::CloseAccount {
+                    account: self.token_account.clone(),
+                    destination,
+                    authority: self.authority.clone(),
+                },
+                &[authority_seeds]
+            ),
+        )
+    }
+}
+
+/// Burn the unsold allocation the carry-over policy sent to be burned, out
+/// of a vault holding `vault_balance`
+pub fn burn_unsold_allocation(
+    vault: &Vault,
+    vault_balance: u64,
+    sale_config: &mut SaleConfig,
+    monthly_limits: &mut MonthlyLimits,
+    event_authority: &AccountInfo,
+    event_authority_bump: u8,
+) -> Result<()> {
+
+    let pending_burn = monthly_limits.pending_burn;
+    if pending_burn == 0 {
+        return Ok(());
+    }
+
+    let vault_burn = sale_config.vault_burn(pending_burn, vault_balance);
+    if vault_burn > 0 {
+        vault.burn(vault_burn)?;
+        sale_config.release(vault_burn)?;
+    }
+
+    monthly_limits.record_burn(pending_burn)?;
+
+    emit_event_cpi(event_authority, event_authority_bump, &UnsoldAllocationBurned {
+        token_amount: pending_burn,
+    })?;
+
+    Ok(())
+}
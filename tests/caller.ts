@@ -0,0 +1,130 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TokenBiu } from "../target/types/token_biu";
+import { TokenBiuCaller } from "../target/types/token_biu_caller";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
+import { assert } from "chai";
+
+const SOL_USD_FEED_ID = "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+// Prefix of the self-CPI instructions `emit_cpi!` sends events through
+const EVENT_IX_TAG = Buffer.from("e445a52e51cb9a1d", "hex");
+
+describe("token_biu_caller", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.TokenBiu as Program<TokenBiu>;
+  const caller = anchor.workspace.TokenBiuCaller as Program<TokenBiuCaller>;
+  const authority = (provider.wallet as anchor.Wallet).payer;
+
+  const saleConfig = Keypair.generate();
+  const recipient = Keypair.generate();
+  let mint: PublicKey;
+
+  const pda = (seeds: (Buffer | Uint8Array)[], programId = program.programId) =>
+    PublicKey.findProgramAddressSync(seeds, programId)[0];
+
+  const programSaleAuthority = pda([Buffer.from("SALE_AUTHORITY"), saleConfig.publicKey.toBuffer()]);
+  const monthlyLimits = pda([Buffer.from("monthly_limits_a"), saleConfig.publicKey.toBuffer()]);
+  const saleStats = pda([Buffer.from("sale_stats"), saleConfig.publicKey.toBuffer()]);
+  const buyer = pda([Buffer.from("buyer")], caller.programId);
+  const walletPurchase = pda([Buffer.from("wallet_purchase"), buyer.toBuffer()]);
+
+  // The sponsored SOL/USD feed `buy_tokens` prices purchases with
+  const priceUpdate = new PythSolanaReceiver({
+    connection: provider.connection,
+    wallet: provider.wallet as anchor.Wallet,
+  }).getPriceFeedAccountAddress(0, SOL_USD_FEED_ID);
+
+  before(async () => {
+    mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    await program.methods
+      .initializeSale(0.005, new anchor.BN(6), new anchor.BN(1_000_000 * 1_000_000))
+      .accounts({
+        authority: authority.publicKey,
+        saleConfig: saleConfig.publicKey,
+        recipient: recipient.publicKey,
+        tokenMint: mint,
+      })
+      .signers([saleConfig])
+      .rpc();
+
+    await program.methods
+      .initializeMonthlyLimits(0, { monthly: {} })
+      .accounts({ saleConfig: saleConfig.publicKey, authority: authority.publicKey })
+      .rpc();
+
+    const funder = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, funder.address, authority, 1_000_000 * 1_000_000);
+    await program.methods
+      .fundVault(new anchor.BN(1_000_000 * 1_000_000))
+      .accounts({
+        saleConfig: saleConfig.publicKey,
+        authority: authority.publicKey,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .activateSale()
+      .accounts({ saleConfig: saleConfig.publicKey, authority: authority.publicKey })
+      .rpc();
+  });
+
+  it("records the calling program when a PDA buys through CPI", async () => {
+    const signature = await caller.methods
+      .buyThroughPda(new anchor.BN(LAMPORTS_PER_SOL / 100))
+      .accountsStrict({
+        payer: authority.publicKey,
+        buyer,
+        saleAuthority: recipient.publicKey,
+        programSaleAuthority,
+        saleConfig: saleConfig.publicKey,
+        authority: authority.publicKey,
+        mint,
+        programTokenAccount: getAssociatedTokenAddressSync(mint, programSaleAuthority, true),
+        buyerTokenAccount: getAssociatedTokenAddressSync(mint, buyer, true),
+        walletPurchase,
+        monthlyLimits,
+        saleStats,
+        priceUpdate,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        eventAuthority: pda([Buffer.from("__event_authority")]),
+        tokenBiuProgram: program.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const transaction = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const keys = transaction.transaction.message.getAccountKeys();
+    const purchase = transaction.meta.innerInstructions
+      .flatMap((inner) => inner.instructions)
+      .filter((ix) => keys.get(ix.programIdIndex).equals(program.programId))
+      .map((ix) => Buffer.from(anchor.utils.bytes.bs58.decode(ix.data)))
+      .filter((data) => data.subarray(0, 8).equals(EVENT_IX_TAG))
+      .map((data) => program.coder.events.decode(data.subarray(8).toString("base64")))
+      .find((event) => event?.name.toLowerCase() === "tokenspurchased");
+
+    assert.ok(purchase, "TokensPurchased was not emitted");
+    assert.ok(purchase.data.callerProgram.equals(caller.programId));
+    assert.ok(purchase.data.buyer.equals(buyer));
+    assert.ok(purchase.data.payer.equals(authority.publicKey));
+    assert.ok(purchase.data.beneficiary.equals(buyer));
+  });
+});
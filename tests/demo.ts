@@ -10,6 +10,7 @@ import {
   getAssociatedTokenAddressSync,
   createAssociatedTokenAccountInstruction,
   getAccount,
+  getOrCreateAssociatedTokenAccount,
 } from "@solana/spl-token";
 import {
   Keypair,
@@ -109,7 +110,7 @@ describe("token_biu", () => {
     try {
       console.log("\n--- Finding program sale authority PDA ---");
       const [authority, bump] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("SALE_AUTHORITY")],
+        [Buffer.from("SALE_AUTHORITY"), saleConfig.publicKey.toBuffer()],
         program.programId
       );
       programSaleAuthority = authority;
@@ -180,6 +181,16 @@ describe("token_biu", () => {
         .signers([wallet, saleConfig])
         .rpc();
       console.log("Sale configuration initialized successfully.\n");
+
+      await program.methods
+        .activateSale()
+        .accounts({
+          saleConfig: saleConfig.publicKey,
+          authority: wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc();
+      console.log("Sale activated.\n");
     } catch (error) {
       console.error("Error during sale initialization:", error);
       throw error;
@@ -229,20 +240,40 @@ describe("token_biu", () => {
     }
 
     try {
-      console.log("\n--- Minting tokens to program token account ---");
+      console.log("\n--- Funding program token account ---");
       // Add extra tokens for safety
       const MINT_AMOUNT = totalMonthlyLimits * 500;
 
       console.log(`Minting ${MINT_AMOUNT} tokens to program account...`);
+      const walletTokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet,
+        mint,
+        wallet.publicKey
+      );
       await mintTo(
         provider.connection,
         wallet,
         mint,
-        programTokenAccount,
+        walletTokenAccount.address,
         wallet.publicKey,
         MINT_AMOUNT
       );
 
+      // Deposits made through fund_vault back the schedule checks
+      await program.methods
+        .fundVault(new anchor.BN(MINT_AMOUNT))
+        .accounts({
+          saleConfig: saleConfig.publicKey,
+          authority: wallet.publicKey,
+          mint,
+          funderTokenAccount: walletTokenAccount.address,
+          programTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([wallet])
+        .rpc();
+
       const tokenAccount = await getAccount(
         provider.connection,
         programTokenAccount
@@ -255,7 +286,7 @@ describe("token_biu", () => {
   });
 
   const [monthlyLimitsAccount, monthlyLimitsBump] = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("monthly_limits_a")],
+    [Buffer.from("monthly_limits_a"), saleConfig.publicKey.toBuffer()],
     program.programId
   );
 
@@ -284,12 +315,25 @@ describe("token_biu", () => {
     console.log("Listener added")
 
     try {
+      await program.methods
+        .initializeMonthlyLimits(monthlyLimits.length, { monthly: {} })
+        .accounts({
+          authority: wallet.publicKey,
+          saleConfig: saleConfig.publicKey,
+          monthlyLimits: monthlyLimitsAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+
       const tx = await program.methods
-        .setMonthlyLimits(monthlyLimits, bnTimestamps)
+        .setMonthlyLimits(monthlyLimits, monthlyLimits.map(() => new anchor.BN(0)), bnTimestamps)
         .accounts({
           authority: wallet.publicKey,
           saleConfig: saleConfig.publicKey,
           monthlyLimits: monthlyLimitsAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .signers([wallet])
@@ -403,7 +447,7 @@ describe("token_biu", () => {
           try {
 
             const remTokens = await program.account.monthlyLimits.fetch(monthlyLimitsAccount);
-            let rem = remTokens.tokensAvailable;
+            let rem = remTokens.saleAvailable;
             let monthlyLimit = new anchor.BN(monthlyValues[month] * 1000000);
             let totalWithdrawAmount = rem.add(monthlyLimit);
 
@@ -454,6 +498,8 @@ describe("token_biu", () => {
           .buyTokens(new anchor.BN(expectedSol * LAMPORTS_PER_SOL), monthlyTimestamp)
           .accounts({
             buyer: buyer.publicKey,
+            payer: buyer.publicKey,
+            beneficiary: buyer.publicKey,
             saleAuthority: recipient,
             programSaleAuthority: programSaleAuthority,
             saleConfig: saleConfig.publicKey,
@@ -482,14 +528,14 @@ describe("token_biu", () => {
 
         // Verify monthly limits state
         // console.log("Total tokens unlocked uptill this point are: ", monthlyLimitsState.tokensUnlocked.toNumber() / 1e6);
-        // console.log("\nTotal tokens available uptill this point are: ", monthlyLimitsState.tokensAvailable.toNumber() / 1e6);
+        // console.log("\nTotal tokens available uptill this point are: ", monthlyLimitsState.saleAvailable.toNumber() / 1e6);
         if (month < 14) {
           console.log(`\nMonth limit: ${monthlyLimitsState.limits[month].toNumber() / 1e6}\n`);
           cumulativeLimit += monthlyLimitsState.limits[month].toNumber();
         }
         console.log(`\nTokens bought in ${month} are: `, (monthlyLimitsState.tokensUnlocked.toNumber() - preBalance) / 1e6, "\n");
-        console.log("CUrrently last checked index is: ", monthlyLimitsState.lastCheckedIndex);
-        console.log(`\nTotal tokens available are:  ${monthlyLimitsState.tokensAvailable.toNumber() / 1e6}\n`);
+        console.log("Periods unlocked so far: ", monthlyLimitsState.periodsUnlocked);
+        console.log(`\nTotal tokens available are:  ${monthlyLimitsState.saleAvailable.toNumber() / 1e6}\n`);
         console.log(`\nTotal Tokens Withdrawan are:  ${monthlyLimitsState.tokensWithdrawn.toNumber() / 1e6}\n`);
 
 
@@ -535,6 +581,8 @@ describe("token_biu", () => {
       .buyTokens(smallPurchase, newTimestamp)
       .accounts({
         buyer: buyer.publicKey,
+        payer: buyer.publicKey,
+        beneficiary: buyer.publicKey,
         saleAuthority: recipient.publicKey,
         programSaleAuthority: programSaleAuthority,
         saleConfig: saleConfig.publicKey,